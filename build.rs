@@ -0,0 +1,33 @@
+fn main() {
+    #[cfg(feature = "ffi")]
+    ffi_header();
+}
+
+/// Writes the header of the C API to `wordl.h` in `OUT_DIR`, the copy in
+/// `include/` is only updated on request, see the tests of `ffi.rs`
+#[cfg(feature = "ffi")]
+fn ffi_header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        include_guard: Some("WORDL_H".to_string()),
+        cpp_compat: true,
+        usize_is_size_t: true,
+        enumeration: cbindgen::EnumConfig {
+            prefix_with_name: true,
+            rename_variants: cbindgen::RenameRule::ScreamingSnakeCase,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(format!("{}/src/ffi.rs", crate_dir))
+        .generate()
+        .expect("the C header could not be generated")
+        .write_to_file(format!("{}/wordl.h", out_dir));
+}
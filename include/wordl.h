@@ -0,0 +1,197 @@
+#ifndef WORDL_H
+#define WORDL_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Size of a buffer holding a word and its NUL
+ */
+#define WORDL_WORD_BUFFER 6
+
+/**
+ * What a function of the C API did
+ */
+typedef enum WordlStatus {
+  WORDL_STATUS_OK = 0,
+  /**
+   * A pointer argument was NULL
+   */
+  WORDL_STATUS_NULL_POINTER = 1,
+  /**
+   * A string argument was not UTF-8
+   */
+  WORDL_STATUS_INVALID_UTF8 = 2,
+  /**
+   * The word is not in the lexicon
+   */
+  WORDL_STATUS_INVALID_WORD = 3,
+  /**
+   * The pattern is not five of C, M and I
+   */
+  WORDL_STATUS_INVALID_PATTERN = 4,
+  /**
+   * No solver goes by that spec
+   */
+  WORDL_STATUS_UNKNOWN_SOLVER = 5,
+  /**
+   * No word fits the attempts pushed so far
+   */
+  WORDL_STATUS_NO_GUESS = 6,
+  /**
+   * The output buffer is too small
+   */
+  WORDL_STATUS_BUFFER_TOO_SMALL = 7,
+  /**
+   * Something went wrong inside the library
+   */
+  WORDL_STATUS_INTERNAL = 8,
+} WordlStatus;
+
+/**
+ * The words of the game, with their n-gram counts. The solvers load the
+ * same words on their own, the lexicon is only there to look words up and
+ * to check the attempts pushed to a solver.
+ */
+typedef struct WordlLexicon WordlLexicon;
+
+/**
+ * A solver and the attempts of the game it is helping with
+ */
+typedef struct WordlSolver WordlSolver;
+
+/**
+ * A guess ranked by a solver
+ */
+typedef struct WordlSuggestion {
+  char word[WORDL_WORD_BUFFER];
+  double score;
+  double entropy;
+  double expected_remaining;
+  size_t worst_case;
+  double win_probability;
+  bool is_candidate;
+} WordlSuggestion;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * A short description of the status, a static string that must not be freed
+ */
+const char *wordl_status_message(enum WordlStatus status);
+
+/**
+ * Writes the pattern the guess gets against the answer, e.g. `IMMII`, to
+ * `out`
+ *
+ * # Safety
+ * `answer` and `guess` are NUL terminated strings and `out` has room for
+ * `out_len` bytes
+ */
+enum WordlStatus wordl_color(const char *answer, const char *guess, char *out, size_t out_len);
+
+/**
+ * The lexicon embedded in the library, free it with `wordl_lexicon_free`
+ */
+struct WordlLexicon *wordl_lexicon_new(void);
+
+/**
+ * # Safety
+ * `lexicon` is NULL or was returned by `wordl_lexicon_new` and not freed yet
+ */
+void wordl_lexicon_free(struct WordlLexicon *lexicon);
+
+/**
+ * Number of words in the lexicon
+ *
+ * # Safety
+ * `lexicon` is NULL or a live lexicon
+ */
+size_t wordl_lexicon_len(const struct WordlLexicon *lexicon);
+
+/**
+ * Whether the word is in the lexicon
+ *
+ * # Safety
+ * `lexicon` is NULL or a live lexicon, `word` is NULL or a NUL terminated
+ * string
+ */
+bool wordl_lexicon_contains(const struct WordlLexicon *lexicon, const char *word);
+
+/**
+ * Creates the solver of the spec, e.g. `entropy` or `naive:prior=raw`, and
+ * stores it in `out`. The lexicon is what `wordl_solver_push` checks words
+ * against, it can be freed before the solver.
+ *
+ * # Safety
+ * `lexicon` is a live lexicon, `spec` a NUL terminated string and `out`
+ * valid for writes
+ */
+enum WordlStatus wordl_solver_new(const struct WordlLexicon *lexicon,
+                                  const char *spec,
+                                  struct WordlSolver **out);
+
+/**
+ * # Safety
+ * `solver` is NULL or was created by `wordl_solver_new` and not freed yet
+ */
+void wordl_solver_free(struct WordlSolver *solver);
+
+/**
+ * Forgets the attempts to start a new game
+ *
+ * # Safety
+ * `solver` is NULL or a live solver
+ */
+enum WordlStatus wordl_solver_reset(struct WordlSolver *solver);
+
+/**
+ * Adds an attempt of the game, the word and its pattern, e.g. `IMMII`
+ *
+ * # Safety
+ * `solver` is NULL or a live solver, `word` and `pattern` are NULL or NUL
+ * terminated strings
+ */
+enum WordlStatus wordl_solver_push(struct WordlSolver *solver,
+                                   const char *word,
+                                   const char *pattern);
+
+/**
+ * Number of attempts pushed since the solver was created or reset
+ *
+ * # Safety
+ * `solver` is NULL or a live solver
+ */
+size_t wordl_solver_attempts(const struct WordlSolver *solver);
+
+/**
+ * Writes the next guess of the solver to `out`
+ *
+ * # Safety
+ * `solver` is NULL or a live solver and `out` has room for `out_len` bytes
+ */
+enum WordlStatus wordl_solver_next_guess(struct WordlSolver *solver, char *out, size_t out_len);
+
+/**
+ * Writes up to `capacity` of the best next guesses to `out`, best first, and
+ * their number to `count`
+ *
+ * # Safety
+ * `solver` is NULL or a live solver, `out` has room for `capacity`
+ * suggestions and `count` is valid for writes
+ */
+enum WordlStatus wordl_solver_suggestions(struct WordlSolver *solver,
+                                          struct WordlSuggestion *out,
+                                          size_t capacity,
+                                          size_t *count);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* WORDL_H */
@@ -2,10 +2,43 @@ mod naive;
 pub use naive::NaiveAlgorithm;
 
 mod entropy;
-pub use entropy::HighestEntropyAlgorithm;
+pub use entropy::{HighestEntropyAlgorithm, WordEntropy};
 
 mod optimized_entropy;
-pub use optimized_entropy::OptimizedEntropyAlgorithm;
+pub use optimized_entropy::{OptimizedEntropyAlgorithm, WordScore};
+
+mod fibble;
+pub use fibble::FibbleSolver;
+
+mod constraints;
+pub use constraints::Constraints;
+
+mod evaluation;
+pub use evaluation::GuessEvaluation;
+
+mod explanation;
+pub use explanation::{Bucket, Explanation};
+
+mod history;
+pub use history::HistoryCache;
+
+mod prior;
+pub use prior::Prior;
+
+mod second_guess;
+pub use second_guess::{SecondGuessCache, SecondGuessTable};
+
+mod opening;
+pub use opening::{Opening, WithOpener};
 
 mod utils;
 pub use utils::Utils;
+
+mod registry;
+pub use registry::{SolverRegistry, SolverSpec};
+
+// there are no processes to start in the browser
+#[cfg(not(target_arch = "wasm32"))]
+mod external;
+#[cfg(not(target_arch = "wasm32"))]
+pub use external::{ExternalError, ExternalSolver};
@@ -0,0 +1,299 @@
+use crate::{Attempt, Score};
+use std::fmt;
+
+/// Bitset with every letter from `a` to `z`
+const ALL_LETTERS: u32 = (1 << 26) - 1;
+
+/// Everything known about the answer after a list of attempts: the letters
+/// allowed at every position and how many times every letter can appear
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Constraints {
+    /// Letters still allowed at every position, as a bitset of `a..=z`
+    allowed: [u32; 5],
+    /// Minimum number of times every letter appears in the answer
+    min: [u8; 26],
+    /// Maximum number of times every letter appears in the answer
+    max: [u8; 26],
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&Attempt<'_>> for Constraints {
+    fn from(attempt: &Attempt) -> Self {
+        let mut constraints = Constraints::new();
+        constraints.add(attempt);
+        constraints
+    }
+}
+
+impl Constraints {
+    /// No constraints at all, every word matches
+    pub fn new() -> Self {
+        Self {
+            allowed: [ALL_LETTERS; 5],
+            min: [0; 26],
+            max: [5; 26],
+        }
+    }
+
+    pub fn from_attempts(attempts: &[Attempt]) -> Self {
+        let mut constraints = Constraints::new();
+        for attempt in attempts {
+            constraints.add(attempt);
+        }
+        constraints
+    }
+
+    /// Narrows the constraints down with what the attempt revealed
+    pub fn add(&mut self, attempt: &Attempt) {
+        let mut found = [0u8; 26];
+        let mut exhausted = [false; 26];
+
+        for (i, (c, score)) in attempt.word.bytes().zip(attempt.score).enumerate().take(5) {
+            let Some(letter) = Constraints::index(c) else {
+                continue;
+            };
+
+            match score {
+                Score::Correct => {
+                    self.allowed[i] &= 1 << letter;
+                    found[letter] += 1;
+                }
+                Score::Misplaced => {
+                    self.allowed[i] &= !(1 << letter);
+                    found[letter] += 1;
+                }
+                Score::Incorrect => {
+                    self.allowed[i] &= !(1 << letter);
+                    exhausted[letter] = true;
+                }
+            }
+        }
+
+        for letter in 0..26 {
+            self.min[letter] = self.min[letter].max(found[letter]);
+            // a grey tile means the answer has no more copies than the coloured ones
+            if exhausted[letter] {
+                self.max[letter] = self.max[letter].min(found[letter]);
+            }
+        }
+    }
+
+    /// Only allows the letter at the position
+    pub fn fix(&mut self, position: usize, c: char) {
+        if let Some(letter) = Constraints::index(c as u8) {
+            self.allowed[position] &= 1 << letter;
+            self.min[letter] = self.min[letter].max(1);
+        }
+    }
+
+    /// Forbids the letter at the position
+    pub fn exclude(&mut self, position: usize, c: char) {
+        if let Some(letter) = Constraints::index(c as u8) {
+            self.allowed[position] &= !(1 << letter);
+        }
+    }
+
+    /// Requires the letter to appear at least `count` times
+    pub fn require(&mut self, c: char, count: u8) {
+        if let Some(letter) = Constraints::index(c as u8) {
+            self.min[letter] = self.min[letter].max(count);
+        }
+    }
+
+    /// Forbids the letter everywhere
+    pub fn ban(&mut self, c: char) {
+        if let Some(letter) = Constraints::index(c as u8) {
+            self.max[letter] = 0;
+        }
+    }
+
+    /// Combines two sets of constraints, a word matches the result only if it
+    /// matches both
+    pub fn merge(&mut self, other: &Constraints) {
+        for i in 0..5 {
+            self.allowed[i] &= other.allowed[i];
+        }
+        for letter in 0..26 {
+            self.min[letter] = self.min[letter].max(other.min[letter]);
+            self.max[letter] = self.max[letter].min(other.max[letter]);
+        }
+    }
+
+    /// Checks if the word can still be the answer
+    pub fn matches(&self, word: &str) -> bool {
+        let word = word.as_bytes();
+        if word.len() != 5 {
+            return false;
+        }
+
+        let mut counts = [0u8; 26];
+        for (i, &c) in word.iter().enumerate() {
+            let Some(letter) = Constraints::index(c) else {
+                return false;
+            };
+            if self.allowed[i] & (1 << letter) == 0 {
+                return false;
+            }
+            counts[letter] += 1;
+        }
+
+        counts
+            .iter()
+            .zip(self.min.iter().zip(self.max.iter()))
+            .all(|(count, (min, max))| min <= count && count <= max)
+    }
+
+    /// The letter known to be at every position
+    pub fn greens(&self) -> [Option<char>; 5] {
+        self.allowed.map(|allowed| {
+            if allowed.count_ones() == 1 {
+                Some(Constraints::letter(allowed.trailing_zeros() as usize))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The letters still allowed at the position
+    pub fn allowed(&self, position: usize) -> impl Iterator<Item = char> + '_ {
+        (0..26)
+            .filter(move |&letter| self.allowed[position] & (1 << letter) != 0)
+            .map(Constraints::letter)
+    }
+
+    /// Minimum number of times the letter appears in the answer
+    pub fn min_count(&self, c: char) -> u8 {
+        Constraints::index(c as u8).map_or(0, |letter| self.min[letter])
+    }
+
+    /// Maximum number of times the letter appears in the answer
+    pub fn max_count(&self, c: char) -> u8 {
+        Constraints::index(c as u8).map_or(0, |letter| self.max[letter])
+    }
+
+    fn index(c: u8) -> Option<usize> {
+        let c = c.to_ascii_lowercase();
+        c.is_ascii_lowercase().then(|| (c - b'a') as usize)
+    }
+
+    fn letter(index: usize) -> char {
+        (b'a' + index as u8) as char
+    }
+}
+
+impl fmt::Display for Constraints {
+    /// Summarizes what is known, e.g. `s?a?e, needs r, no t/o/n`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let greens = self.greens();
+        for green in greens {
+            write!(f, "{}", green.unwrap_or('?'))?;
+        }
+
+        let mut needed = vec![];
+        let mut banned = vec![];
+        for letter in 0..26 {
+            let c = Constraints::letter(letter);
+            let placed = greens.iter().filter(|&&green| green == Some(c)).count() as u8;
+            if self.min[letter] > placed {
+                needed.push(c.to_string().repeat((self.min[letter] - placed) as usize));
+            }
+            if self.max[letter] == 0 {
+                banned.push(c.to_string());
+            }
+        }
+
+        if !needed.is_empty() {
+            write!(f, ", needs {}", needed.join("/"))?;
+        }
+        if !banned.is_empty() {
+            write!(f, ", no {}", banned.join("/"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    fn attempt(word: &'static str, answer: &str) -> Attempt<'static> {
+        Attempt {
+            word: Cow::Borrowed(word),
+            score: Score::color(answer, word),
+        }
+    }
+
+    #[test]
+    fn test_answer_always_matches() {
+        let answers = ["sauce", "eerie", "abbey", "llama", "wrung"];
+        let guesses = ["tares", "eeeee", "bobby", "lilac", "array", "crane"];
+        for answer in answers {
+            let attempts: Vec<_> = guesses.iter().map(|g| attempt(g, answer)).collect();
+            assert!(Constraints::from_attempts(&attempts).matches(answer));
+        }
+    }
+
+    #[test]
+    fn test_matches_exactly_the_words_giving_the_same_feedback() {
+        let words: Vec<&str> = include_str!("./../../dataset/wordle/ans.txt")
+            .lines()
+            .collect();
+        for (answer, guess) in [("abbey", "babes"), ("eerie", "elder"), ("llama", "alley")] {
+            let constraints = Constraints::from(&attempt(guess, answer));
+            for word in &words {
+                assert_eq!(
+                    constraints.matches(word),
+                    Score::color(word, guess) == Score::color(answer, guess),
+                    "{} vs {} for answer {}",
+                    word,
+                    guess,
+                    answer
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_repeated_letter_with_a_grey_tile_caps_the_count() {
+        let constraints = Constraints::from(&attempt("eerie", "there"));
+        assert_eq!(constraints.min_count('e'), 2);
+        assert_eq!(constraints.max_count('e'), 2);
+        assert!(!constraints.matches("eeeee"));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut constraints = Constraints::from(&attempt("tares", "snake"));
+        constraints.merge(&Constraints::from(&attempt("olden", "snake")));
+        assert_eq!(
+            constraints,
+            Constraints::from_attempts(&[attempt("tares", "snake"), attempt("olden", "snake")])
+        );
+        assert!(constraints.matches("snake"));
+        assert!(!constraints.matches("shake"));
+    }
+
+    #[test]
+    fn test_display_summary() {
+        assert_eq!(Constraints::new().to_string(), "?????");
+
+        let constraints = Constraints::from_attempts(&[attempt("snore", "stage")]);
+        assert_eq!(
+            constraints.greens(),
+            [Some('s'), None, None, None, Some('e')]
+        );
+        assert_eq!(constraints.to_string(), "s???e, no n/o/r");
+
+        let constraints = Constraints::from_attempts(&[attempt("shave", "stare")]);
+        assert_eq!(constraints.to_string(), "s?a?e, no h/v");
+
+        let constraints = Constraints::from_attempts(&[attempt("trope", "stare")]);
+        assert_eq!(constraints.to_string(), "????e, needs r/t, no o/p");
+    }
+}
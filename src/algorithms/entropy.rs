@@ -1,8 +1,14 @@
-use super::Utils;
+use super::{
+    Bucket, Constraints, GuessEvaluation, HistoryCache, Opening, Prior, Utils, WithOpener,
+};
 use crate::{Attempt, Score, Solver};
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+};
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct WordEntropy {
     pub word: &'static str,
     pub entropy: f64,
@@ -15,124 +21,245 @@ impl WordEntropy {
 }
 
 // Maximum entropy calculation
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct HighestEntropyAlgorithm {
-    pub available_options: HashMap<&'static str, u32>,
+    pub available_options: BTreeMap<&'static str, u32>,
+    /// The prior used to weight each word as a possible answer
+    pub prior: Prior,
+    /// Weight of every word in the lexicon according to `prior`
+    pub weights: HashMap<&'static str, f64>,
+    /// The attempts `available_options` was filtered with
+    pub history: HistoryCache,
+    /// The first guess of every game
+    pub opening: Opening,
+}
+
+impl Default for HighestEntropyAlgorithm {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl HighestEntropyAlgorithm {
     pub fn new() -> Self {
-        let n_gram_frequency = include_str!("./../../dataset/wordle_words_x_n_gram.txt");
-        let available_options = n_gram_frequency
-            .lines()
-            .map(|line| {
-                let mut parts = line.split_whitespace();
-                let word = parts.next().unwrap();
-                let freq = parts.next().unwrap().parse().unwrap();
-                (word, freq)
-            })
-            .collect();
+        Self::with_prior(Prior::Uniform)
+    }
 
-        Self { available_options }
+    pub fn with_prior(prior: Prior) -> Self {
+        let available_options = Utils::word_frequencies();
+        let weights = prior.weights(&available_options);
+        let history = HistoryCache::new(available_options.clone());
+
+        Self {
+            available_options,
+            prior,
+            weights,
+            history,
+            opening: Opening::new(Some("tares")),
+        }
     }
 
     pub fn calculate_entropy(
         word: &'static str,
-        available_options: HashMap<&'static str, u32>,
+        available_options: BTreeMap<&'static str, u32>,
+        weights: &HashMap<&'static str, f64>,
     ) -> WordEntropy {
-        let mut entropy = 0.0;
-        let count = available_options.len();
+        let evaluation = HighestEntropyAlgorithm::evaluate(word, &available_options, weights);
+        WordEntropy::new(word, evaluation.entropy)
+    }
 
-        // println!("Total Count For Entropy Calculations : {}", count);
+    pub fn evaluate(
+        word: &'static str,
+        available_options: &BTreeMap<&'static str, u32>,
+        weights: &HashMap<&'static str, f64>,
+    ) -> GuessEvaluation {
+        let weighted = HighestEntropyAlgorithm::is_weighted(available_options, weights);
+        let mass = |option: &&'static str| {
+            if weighted {
+                weights.get(option).copied().unwrap_or(0.0)
+            } else {
+                1.0
+            }
+        };
+
+        // size and probability mass of every feedback bucket, by `Score::index`
+        let mut counts = [0usize; 243];
+        let mut masses = [0f64; 243];
+        for option in available_options.keys() {
+            let index = Score::index(&Score::color(option, word));
+            counts[index] += 1;
+            masses[index] += mass(option);
+        }
+        let total: f64 = masses.iter().sum();
+
+        let mut entropy = 0.0;
+        let mut expected_remaining = 0.0;
+        let mut worst_case = 0;
 
         // Calculate the entropy of the word
         // using the formula: -p * log2(p)
-        // where p is the probability of the word
+        // where p is the probability mass of the words left after the feedback
 
-        for possibility in Score::permutations() {
-            // if possibility == [Score::Correct; 5] {
-            //     println!("Skipping as all the letters are correct.");
-            //     continue;
-            // }
+        for (&count, &bucket_mass) in counts.iter().zip(masses.iter()) {
+            if count == 0 {
+                continue;
+            }
 
-            let mut possible_options = available_options.clone();
+            let probability = bucket_mass / total;
+            if probability > 0.0 {
+                entropy += -(probability * probability.log2());
+            }
+            expected_remaining += probability * count as f64;
+            worst_case = worst_case.max(count);
+        }
 
-            let attempt = Attempt {
-                word: std::borrow::Cow::Borrowed(word),
-                score: possibility,
-            };
+        let is_candidate = available_options.contains_key(word);
+        let win_probability = if is_candidate {
+            mass(&word) / total
+        } else {
+            0.0
+        };
+
+        GuessEvaluation {
+            word: Cow::Borrowed(word),
+            score: entropy,
+            entropy,
+            expected_remaining,
+            worst_case,
+            win_probability,
+            is_candidate,
+            frequency: available_options.get(word).copied().unwrap_or(0),
+        }
+    }
 
-            let count_after_chosing = HighestEntropyAlgorithm::fetch_length_for_updated_dictionary(
-                &attempt,
-                &mut possible_options,
-            ) as f64;
+    /// Evaluates every available option as a guess, the best first
+    pub fn rank_all(
+        available_options: &BTreeMap<&'static str, u32>,
+        weights: &HashMap<&'static str, f64>,
+    ) -> Vec<GuessEvaluation> {
+        let words: Vec<&'static str> = available_options.keys().copied().collect();
+        let mut evaluations = GuessEvaluation::evaluate_all(&words, |word| {
+            HighestEntropyAlgorithm::evaluate(word, available_options, weights)
+        });
+        GuessEvaluation::sort(&mut evaluations);
+        evaluations
+    }
 
-            if count_after_chosing == 0.0 {
+    /// Splits the available options by the feedback the word would get, in the
+    /// order of `Score::permutations`, skipping the feedback no word can give
+    pub fn buckets(
+        word: &str,
+        available_options: &BTreeMap<&'static str, u32>,
+        weights: &HashMap<&'static str, f64>,
+    ) -> Vec<Bucket> {
+        let weighted = HighestEntropyAlgorithm::is_weighted(available_options, weights);
+        let mass = |options: &[&'static str]| {
+            if weighted {
+                Prior::mass(options.iter(), weights)
+            } else {
+                options.len() as f64
+            }
+        };
+        let weight = |word: &&'static str| weights.get(word).copied().unwrap_or(0.0);
+
+        let mut words_by_pattern: Vec<Vec<&'static str>> = vec![vec![]; 243];
+        for &option in available_options.keys() {
+            words_by_pattern[Score::index(&Score::color(option, word))].push(option);
+        }
+        let total = mass(&available_options.keys().copied().collect::<Vec<_>>());
+
+        let mut buckets = vec![];
+
+        for (index, mut words) in words_by_pattern.into_iter().enumerate() {
+            if words.is_empty() {
                 continue;
             }
 
-            let probability = count_after_chosing / count as f64;
+            words.sort_by(|a, b| weight(b).partial_cmp(&weight(a)).unwrap().then(a.cmp(b)));
 
-            entropy += (probability * probability.log2()) * -1 as f64;
+            let probability = mass(&words) / total;
+            let entropy = if probability > 0.0 {
+                -(probability * probability.log2())
+            } else {
+                0.0
+            };
+
+            buckets.push(Bucket {
+                pattern: Score::from_index(index),
+                words,
+                probability,
+                entropy,
+            });
         }
 
-        WordEntropy::new(word, entropy)
+        buckets
+    }
+
+    /// Whether the remaining words carry any weight, if not every word counts as one
+    fn is_weighted(
+        available_options: &BTreeMap<&'static str, u32>,
+        weights: &HashMap<&'static str, f64>,
+    ) -> bool {
+        Prior::mass(available_options.keys(), weights) > 0.0
     }
 
     pub fn fetch_length_for_updated_dictionary(
         attempt: &Attempt,
-        available_options: &mut HashMap<&'static str, u32>,
+        available_options: &mut BTreeMap<&'static str, u32>,
     ) -> usize {
-        // Remove the last word from the available options
-        // since it is not the correct answer
-        available_options.retain(|word, _| {
-            return Utils::if_attempt_is_similar_to_word(&attempt, word);
-        });
+        // Remove the words that would not have given this feedback
+        let constraints = Constraints::from(attempt);
+        available_options.retain(|word, _| constraints.matches(word));
 
-        return available_options.len();
+        available_options.len()
     }
 }
 
-impl Solver for HighestEntropyAlgorithm {
-    fn solve(&mut self, history: &[Attempt]) -> String {
-        let last_attempt = history.last();
-
-        // used to reduce the time on first attempt
-        if last_attempt.is_none() {
-            return "tares".to_string();
-        }
-
-        let last_try = last_attempt.unwrap();
-        let last_word = last_try.word.as_ref();
+impl WithOpener for HighestEntropyAlgorithm {
+    fn opening(&mut self) -> (&mut Opening, &BTreeMap<&'static str, u32>) {
+        (&mut self.opening, &self.history.lexicon)
+    }
+}
 
-        // println!("Last {:?}", last_try);
+impl Solver for HighestEntropyAlgorithm {
+    fn name(&self) -> &str {
+        "entropy"
+    }
 
-        self.available_options.remove(last_word);
-        HighestEntropyAlgorithm::fetch_length_for_updated_dictionary(
-            &last_try,
-            &mut self.available_options,
-        );
+    fn reset(&mut self) {
+        self.history.reset(&mut self.available_options);
+    }
 
-        let mut word_entropies: Vec<WordEntropy> = vec![];
+    fn rank(&mut self, history: &[Attempt], n: usize) -> Vec<GuessEvaluation> {
+        self.history.sync(history, &mut self.available_options);
 
-        for (word, _) in self.available_options.iter() {
-            let word_entropy =
-                HighestEntropyAlgorithm::calculate_entropy(*word, self.available_options.clone());
-            word_entropies.push(word_entropy);
+        let (available_options, weights) = (&self.available_options, &self.weights);
+        if history.is_empty() {
+            return self.opening.rank(
+                n,
+                |opener| HighestEntropyAlgorithm::evaluate(opener, available_options, weights),
+                || HighestEntropyAlgorithm::rank_all(available_options, weights),
+            );
         }
 
-        word_entropies.sort_by(|a, b| a.entropy.partial_cmp(&b.entropy).unwrap());
+        let mut evaluations = HighestEntropyAlgorithm::rank_all(available_options, weights);
+        evaluations.truncate(n);
+        evaluations
+    }
 
-        let result = word_entropies.last().unwrap().word.to_string();
-        // println!("{:?}", result);
-        return result;
+    fn buckets(&mut self, history: &[Attempt], guess: &str) -> Option<Vec<Bucket>> {
+        self.history.sync(history, &mut self.available_options);
+        Some(HighestEntropyAlgorithm::buckets(
+            guess,
+            &self.available_options,
+            &self.weights,
+        ))
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
-    use std::borrow::Cow;
-
     use super::*;
     use crate::Attempt;
     use crate::Score;
@@ -211,7 +338,143 @@ mod tests {
         let result = HighestEntropyAlgorithm::calculate_entropy(
             "corms",
             highest_entropy_algorithm.available_options,
+            &highest_entropy_algorithm.weights,
         );
         assert_eq!(result.entropy, 5.137219911431635);
     }
+
+    #[test]
+    fn test_calculate_entropy_with_prior_weights() {
+        let highest_entropy_algorithm = HighestEntropyAlgorithm::with_prior(Prior::Answers);
+        let available_options: BTreeMap<&'static str, u32> =
+            [("aback", 1), ("abase", 1), ("aahed", 1)].into();
+
+        // "aahed" has no weight, so only the split between the two answers counts
+        let result = HighestEntropyAlgorithm::calculate_entropy(
+            "abide",
+            available_options,
+            &highest_entropy_algorithm.weights,
+        );
+        assert_eq!(result.entropy, 1.0);
+    }
+
+    #[test]
+    fn test_evaluate_reports_remaining_candidates_and_win_probability() {
+        let highest_entropy_algorithm = HighestEntropyAlgorithm::new();
+        let available_options: BTreeMap<&'static str, u32> =
+            [("aback", 1), ("abase", 1), ("abate", 1), ("abbey", 1)].into();
+
+        // "abase" and "abate" land in the same bucket, the other two are singletons
+        let result = HighestEntropyAlgorithm::evaluate(
+            "abide",
+            &available_options,
+            &highest_entropy_algorithm.weights,
+        );
+        assert_eq!(result.entropy, 1.5);
+        assert_eq!(result.expected_remaining, 1.5);
+        assert_eq!(result.worst_case, 2);
+        assert_eq!(result.win_probability, 0.0);
+        assert!(!result.is_candidate);
+
+        let result = HighestEntropyAlgorithm::evaluate(
+            "abbey",
+            &available_options,
+            &highest_entropy_algorithm.weights,
+        );
+        assert_eq!(result.win_probability, 0.25);
+        assert!(result.is_candidate);
+    }
+
+    #[test]
+    fn test_rank_returns_best_guesses_first() {
+        let mut highest_entropy_algorithm = HighestEntropyAlgorithm::new();
+        let history = [Attempt {
+            word: Cow::Borrowed("tares"),
+            score: [
+                Score::Incorrect,
+                Score::Correct,
+                Score::Correct,
+                Score::Incorrect,
+                Score::Incorrect,
+            ],
+        }];
+
+        let ranked = highest_entropy_algorithm.rank(&history, 3);
+        assert_eq!(ranked.len(), 3);
+        assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+        assert!(ranked.iter().all(|e| e.is_candidate));
+        assert_eq!(highest_entropy_algorithm.solve(&history), ranked[0].word);
+    }
+
+    #[test]
+    fn test_rank_the_first_move_after_the_opener() {
+        let mut highest_entropy_algorithm = HighestEntropyAlgorithm::new();
+        highest_entropy_algorithm.available_options =
+            [("years", 0), ("aback", 0), ("which", 0), ("tares", 0)].into();
+
+        assert_eq!(highest_entropy_algorithm.rank(&[], 1).len(), 1);
+        let ranked = highest_entropy_algorithm.rank(&[], 3);
+        assert_eq!(ranked.len(), 3);
+        assert_eq!(ranked[0].word, "tares");
+        assert!(ranked[1..].iter().all(|e| e.word != "tares"));
+        assert!(ranked[1].score >= ranked[2].score);
+    }
+
+    #[test]
+    fn test_reset_makes_every_word_available_again() {
+        let mut highest_entropy_algorithm = HighestEntropyAlgorithm::new();
+        let history = [Attempt {
+            word: Cow::Borrowed("tares"),
+            score: Score::color("cigar", "tares"),
+        }];
+        highest_entropy_algorithm.solve(&history);
+        assert!(highest_entropy_algorithm.available_options.len() < 12972);
+
+        highest_entropy_algorithm.reset();
+        assert!(highest_entropy_algorithm.history.is_empty());
+        assert_eq!(highest_entropy_algorithm.available_options.len(), 12972);
+        assert_eq!(highest_entropy_algorithm.name(), "entropy");
+    }
+
+    #[test]
+    fn test_buckets_cover_every_candidate() {
+        let highest_entropy_algorithm = HighestEntropyAlgorithm::new();
+        let available_options: BTreeMap<&'static str, u32> =
+            [("aback", 1), ("abase", 1), ("abate", 1), ("abbey", 1)].into();
+
+        let buckets = HighestEntropyAlgorithm::buckets(
+            "abide",
+            &available_options,
+            &highest_entropy_algorithm.weights,
+        );
+        assert_eq!(buckets.len(), 3);
+        assert_eq!(buckets.iter().map(|b| b.words.len()).sum::<usize>(), 4);
+        assert_eq!(buckets.iter().map(|b| b.probability).sum::<f64>(), 1.0);
+
+        let shared = buckets.iter().find(|b| b.words.len() == 2).unwrap();
+        assert_eq!(shared.words, ["abase", "abate"]);
+        assert_eq!(shared.entropy, 0.5);
+    }
+
+    #[test]
+    fn test_explain_compares_with_the_runner_up() {
+        let mut highest_entropy_algorithm = HighestEntropyAlgorithm::new();
+        let history = [Attempt {
+            word: Cow::Borrowed("tares"),
+            score: [
+                Score::Incorrect,
+                Score::Correct,
+                Score::Correct,
+                Score::Incorrect,
+                Score::Incorrect,
+            ],
+        }];
+
+        let explanation = highest_entropy_algorithm.explain(&history).unwrap();
+        let runner_up = explanation.runner_up.as_ref().unwrap();
+        assert!(explanation.guess.score >= runner_up.score);
+        let entropy: f64 = explanation.buckets.iter().map(|b| b.entropy).sum();
+        assert!((entropy - explanation.guess.entropy).abs() < 1e-9);
+        assert!(explanation.to_string().contains("Runner-up"));
+    }
 }
@@ -0,0 +1,123 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::{borrow::Cow, cmp::Ordering};
+
+/// Everything a solver knows about how good a guess is against the words
+/// that are still possible
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuessEvaluation {
+    pub word: Cow<'static, str>,
+    /// The value the solver ranks guesses by, higher is better
+    pub score: f64,
+    /// Expected information gained by the guess in bits, computed over the
+    /// probability mass of every feedback bucket
+    pub entropy: f64,
+    /// Number of candidates expected to be left after the feedback
+    pub expected_remaining: f64,
+    /// Number of candidates left in the largest feedback bucket
+    pub worst_case: usize,
+    /// Probability that this guess is the answer
+    pub win_probability: f64,
+    /// Whether the guess itself can still be the answer
+    pub is_candidate: bool,
+    /// How common the guess is in the word list, 0 when it is not one of the
+    /// candidates
+    pub frequency: u32,
+}
+
+impl GuessEvaluation {
+    /// An evaluation for a guess the solver did not score
+    pub fn unscored(word: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            word: word.into(),
+            score: 0.0,
+            entropy: 0.0,
+            expected_remaining: 0.0,
+            worst_case: 0,
+            win_probability: 0.0,
+            is_candidate: false,
+            frequency: 0,
+        }
+    }
+
+    /// The order every solver ranks guesses in: higher score first, then
+    /// guesses that can still be the answer, then more common words, then
+    /// alphabetical. Every step is total, so a ranking never depends on the
+    /// order the words were evaluated in.
+    pub fn cmp_rank(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .total_cmp(&self.score)
+            .then_with(|| other.is_candidate.cmp(&self.is_candidate))
+            .then_with(|| other.frequency.cmp(&self.frequency))
+            .then_with(|| self.word.cmp(&other.word))
+    }
+
+    /// Sorts evaluations best first, see [`GuessEvaluation::cmp_rank`]
+    pub fn sort(evaluations: &mut [GuessEvaluation]) {
+        evaluations.sort_by(Self::cmp_rank);
+    }
+}
+
+impl GuessEvaluation {
+    /// Evaluates every word, on every core with the `parallel` feature. The
+    /// evaluations come back in the order of `words` either way, so sorting
+    /// them breaks ties the same way.
+    pub fn evaluate_all<F>(words: &[&'static str], evaluate: F) -> Vec<GuessEvaluation>
+    where
+        F: Fn(&'static str) -> GuessEvaluation + Sync + Send,
+    {
+        #[cfg(feature = "parallel")]
+        let evaluations = words.par_iter().map(|&word| evaluate(word)).collect();
+        #[cfg(not(feature = "parallel"))]
+        let evaluations = words.iter().map(|&word| evaluate(word)).collect();
+
+        evaluations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{HighestEntropyAlgorithm, Utils};
+
+    #[test]
+    fn test_evaluate_all_keeps_the_order_of_the_words() {
+        let highest_entropy_algorithm = HighestEntropyAlgorithm::new();
+        let lexicon = Utils::word_frequencies();
+        let mut words: Vec<&'static str> = lexicon.keys().copied().collect();
+        words.sort();
+        words.truncate(200);
+
+        let evaluate = |word| {
+            HighestEntropyAlgorithm::evaluate(
+                word,
+                &highest_entropy_algorithm.available_options,
+                &highest_entropy_algorithm.weights,
+            )
+        };
+        let sequential: Vec<_> = words.iter().map(|&word| evaluate(word)).collect();
+        assert_eq!(GuessEvaluation::evaluate_all(&words, evaluate), sequential);
+    }
+
+    #[test]
+    fn test_sort_breaks_ties_by_candidate_then_frequency_then_word() {
+        let evaluation = |word: &'static str, score, is_candidate, frequency| GuessEvaluation {
+            score,
+            is_candidate,
+            frequency,
+            ..GuessEvaluation::unscored(word)
+        };
+        let mut evaluations = vec![
+            evaluation("zesty", 1.0, false, 0),
+            evaluation("cabin", 1.0, true, 10),
+            evaluation("abbey", 1.0, true, 10),
+            evaluation("crane", 1.0, true, 50),
+            evaluation("slate", 2.0, false, 0),
+        ];
+        GuessEvaluation::sort(&mut evaluations);
+
+        let words: Vec<_> = evaluations.iter().map(|e| e.word.as_ref()).collect();
+        assert_eq!(words, ["slate", "crane", "abbey", "cabin", "zesty"]);
+    }
+}
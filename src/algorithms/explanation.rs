@@ -0,0 +1,111 @@
+use super::GuessEvaluation;
+use crate::Score;
+use std::fmt;
+
+/// Number of buckets and sample words shown when an explanation is printed
+const SHOWN_BUCKETS: usize = 10;
+const SAMPLE_WORDS: usize = 3;
+
+/// The candidates that would be left if a guess got a particular feedback
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    pub pattern: [Score; 5],
+    /// The candidates in the bucket, most likely first
+    pub words: Vec<&'static str>,
+    /// Probability mass of the bucket
+    pub probability: f64,
+    /// Contribution of the bucket to the entropy of the guess: -p * log2(p)
+    pub entropy: f64,
+}
+
+/// Why a solver picked its guess
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    pub guess: GuessEvaluation,
+    /// Every feedback bucket of the guess with at least one candidate, the
+    /// most likely first
+    pub buckets: Vec<Bucket>,
+    /// The second best guess, if there was one
+    pub runner_up: Option<GuessEvaluation>,
+}
+
+impl Explanation {
+    pub fn new(
+        guess: GuessEvaluation,
+        mut buckets: Vec<Bucket>,
+        runner_up: Option<GuessEvaluation>,
+    ) -> Self {
+        buckets.sort_by(|a, b| b.probability.partial_cmp(&a.probability).unwrap());
+        Self {
+            guess,
+            buckets,
+            runner_up,
+        }
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guess = &self.guess;
+        writeln!(
+            f,
+            "'{}' scores {:.3}: {:.3} bits, {:.1} candidates expected to be left, {} in the worst case, {:.1}% chance to win now",
+            guess.word,
+            guess.score,
+            guess.entropy,
+            guess.expected_remaining,
+            guess.worst_case,
+            guess.win_probability * 100.0
+        )?;
+
+        writeln!(f, "  pattern  words  probability   bits  sample")?;
+        for bucket in self.buckets.iter().take(SHOWN_BUCKETS) {
+            let pattern = Score::to_pattern(&bucket.pattern);
+            let sample = bucket
+                .words
+                .iter()
+                .take(SAMPLE_WORDS)
+                .copied()
+                .collect::<Vec<_>>()
+                .join(", ");
+            let more = if bucket.words.len() > SAMPLE_WORDS {
+                ", ..."
+            } else {
+                ""
+            };
+            writeln!(
+                f,
+                "  {:<7}  {:>5}  {:>10.2}%  {:.3}  {}{}",
+                pattern,
+                bucket.words.len(),
+                bucket.probability * 100.0,
+                bucket.entropy,
+                sample,
+                more
+            )?;
+        }
+        if self.buckets.len() > SHOWN_BUCKETS {
+            writeln!(
+                f,
+                "  ... {} more buckets",
+                self.buckets.len() - SHOWN_BUCKETS
+            )?;
+        }
+
+        match &self.runner_up {
+            Some(runner_up) => write!(
+                f,
+                "Runner-up '{}' scores {:.3} ({:+.3}): {:.3} bits ({:+.3}), {:.1} expected left ({:+.1}), {} in the worst case",
+                runner_up.word,
+                runner_up.score,
+                runner_up.score - guess.score,
+                runner_up.entropy,
+                runner_up.entropy - guess.entropy,
+                runner_up.expected_remaining,
+                runner_up.expected_remaining - guess.expected_remaining,
+                runner_up.worst_case
+            ),
+            None => write!(f, "There was no other guess to compare with"),
+        }
+    }
+}
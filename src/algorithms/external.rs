@@ -0,0 +1,290 @@
+//! A solver running in another process, so solvers written in any language
+//! can be benchmarked like the ones of this crate.
+//!
+//! The protocol is line based. For every guess the program gets the history
+//! of the game on its stdin and answers with a single word on its stdout:
+//!
+//! ```text
+//! > guess
+//! < tares
+//! > guess tares:IMCII
+//! < crane
+//! > guess tares:IMCII crane:CCCCI
+//! < crank
+//! ```
+//!
+//! Each attempt is the guessed word and its feedback, `C` for correct, `M`
+//! for misplaced and `I` for incorrect. Before every new game the program is
+//! sent `new`, which it can use to drop whatever it remembers about the last
+//! game, it does not answer to it. Anything the program writes to its stderr
+//! is shown as is. A program that does not answer in time, or answers
+//! something else than a word, is restarted before its next guess.
+
+use super::GuessEvaluation;
+use crate::{Attempt, Solver};
+use std::{
+    fmt, io,
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+/// Why the program could not give a guess
+#[derive(Debug)]
+pub enum ExternalError {
+    /// The program could not be started or talked to
+    Io(io::Error),
+    /// The program did not answer in time
+    Timeout(Duration),
+    /// The program closed its stdout
+    Exited,
+    /// The program answered something that is not a word
+    Protocol(String),
+}
+
+impl fmt::Display for ExternalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExternalError::Io(err) => write!(f, "{}", err),
+            ExternalError::Timeout(timeout) => {
+                write!(f, "no answer within {} ms", timeout.as_millis())
+            }
+            ExternalError::Exited => write!(f, "the program exited"),
+            ExternalError::Protocol(line) => {
+                write!(f, "expected a five letter word, got '{}'", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExternalError {}
+
+impl From<io::Error> for ExternalError {
+    fn from(err: io::Error) -> Self {
+        ExternalError::Io(err)
+    }
+}
+
+/// Runs a program speaking the protocol of this module and asks it for
+/// every guess
+pub struct ExternalSolver {
+    command: String,
+    child: Child,
+    stdin: ChildStdin,
+    /// Lines of the stdout of the program, read on their own thread so
+    /// waiting for them can time out
+    lines: Receiver<io::Result<String>>,
+    timeout: Duration,
+}
+
+impl ExternalSolver {
+    /// How long the program gets for a guess unless told otherwise
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Starts the program, `command` is split on whitespace into the program
+    /// and its arguments
+    pub fn spawn(command: &str, timeout: Duration) -> Result<Self, ExternalError> {
+        let mut parts = command.split_whitespace();
+        let Some(program) = parts.next() else {
+            return Err(ExternalError::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "empty command",
+            )));
+        };
+
+        let mut child = Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            command: command.to_string(),
+            child,
+            stdin,
+            lines,
+            timeout,
+        })
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Tells the program a new game starts
+    pub fn try_reset(&mut self) -> Result<(), ExternalError> {
+        writeln!(self.stdin, "new")?;
+        self.stdin.flush()?;
+        Ok(())
+    }
+
+    /// Sends the history to the program and waits for its guess
+    pub fn try_guess(&mut self, history: &[Attempt]) -> Result<String, ExternalError> {
+        let guess = self.request(history);
+        if let Err(ExternalError::Timeout(_) | ExternalError::Protocol(_)) = guess {
+            // a late or extra line would be read as the answer to the next guess
+            *self = ExternalSolver::spawn(&self.command, self.timeout)?;
+        }
+        guess
+    }
+
+    fn request(&mut self, history: &[Attempt]) -> Result<String, ExternalError> {
+        let mut request = String::from("guess");
+        for attempt in history {
+            request.push_str(&format!(" {}", attempt));
+        }
+        writeln!(self.stdin, "{}", request)?;
+        self.stdin.flush()?;
+
+        let line = match self.lines.recv_timeout(self.timeout) {
+            Ok(line) => line?,
+            Err(RecvTimeoutError::Timeout) => return Err(ExternalError::Timeout(self.timeout)),
+            Err(RecvTimeoutError::Disconnected) => return Err(ExternalError::Exited),
+        };
+
+        let guess = line.trim().to_ascii_lowercase();
+        if guess.len() != 5 || !guess.bytes().all(|b| b.is_ascii_lowercase()) {
+            return Err(ExternalError::Protocol(line));
+        }
+        Ok(guess)
+    }
+}
+
+impl Drop for ExternalSolver {
+    fn drop(&mut self) {
+        // the program may be stuck on a guess, it is not waited for politely
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Solver for ExternalSolver {
+    fn name(&self) -> &str {
+        "external"
+    }
+
+    fn reset(&mut self) {
+        // a program that cannot be told fails on its next guess
+        let _ = self.try_reset();
+    }
+
+    /// The guess of the program, none when it failed, see `try_solve` for why
+    fn rank(&mut self, history: &[Attempt], _n: usize) -> Vec<GuessEvaluation> {
+        self.try_guess(history)
+            .map(GuessEvaluation::unscored)
+            .into_iter()
+            .collect()
+    }
+
+    fn try_solve(&mut self, history: &[Attempt]) -> Result<String, String> {
+        self.try_guess(history)
+            .map_err(|err| format!("external solver '{}' failed: {}", self.command, err))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::{Score, Wordle};
+    use std::{
+        borrow::Cow,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    fn bot(script: &str) -> Result<ExternalSolver, ExternalError> {
+        static BOTS: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "wordl-bot-{}-{}.sh",
+            std::process::id(),
+            BOTS.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&path, script).unwrap();
+        ExternalSolver::spawn(
+            &format!("sh {}", path.display()),
+            Duration::from_millis(500),
+        )
+    }
+
+    #[test]
+    fn test_sends_the_history_and_reads_the_guess() {
+        // answers with the last attempt it was sent, or tares
+        let mut solver = bot("while read line; do\n\
+             case $line in new) ;; guess) echo tares ;; *) echo ${line##* } | cut -c1-5 ;; esac\n\
+             done\n")
+        .unwrap();
+
+        assert_eq!(solver.try_guess(&[]).unwrap(), "tares");
+        let history = [Attempt {
+            word: Cow::Borrowed("tares"),
+            score: Score::color("cigar", "tares"),
+        }];
+        solver.reset();
+        assert_eq!(solver.try_guess(&history).unwrap(), "tares");
+        let history = [Attempt {
+            word: Cow::Borrowed("cigar"),
+            score: [Score::Correct; 5],
+        }];
+        assert_eq!(solver.try_guess(&history).unwrap(), "cigar");
+    }
+
+    #[test]
+    fn test_plays_a_game() {
+        let solver = bot("while read line; do\n\
+             case $line in new) ;; guess) echo tares ;; *) echo cigar ;; esac\n\
+             done\n")
+        .unwrap();
+        let history = Wordle::new()
+            .simulate("cigar", solver, Wordle::MAX_SIMULATED_GUESSES)
+            .unwrap();
+        assert_eq!(history.len(), 2);
+
+        // a bot that stopped answering ends the game with an error
+        let solver = bot("exit 0\n").unwrap();
+        let result = Wordle::new().simulate("cigar", solver, Wordle::MAX_SIMULATED_GUESSES);
+        assert!(result.unwrap_err().starts_with("external solver"));
+    }
+
+    #[test]
+    fn test_reports_errors() {
+        let mut solver = bot("read line; echo hello world\n").unwrap();
+        assert!(matches!(
+            solver.try_guess(&[]),
+            Err(ExternalError::Protocol(_))
+        ));
+
+        // answers late to the first guess only, the restarted bot is on time
+        let dir = std::env::temp_dir().join(format!("wordl-late-bot-{}", std::process::id()));
+        let mut solver = bot(&format!(
+            "while read line; do\n\
+             if mkdir {} 2>/dev/null; then sleep 1; echo crane; else echo tares; fi\n\
+             done\n",
+            dir.display()
+        ))
+        .unwrap();
+        assert!(matches!(
+            solver.try_guess(&[]),
+            Err(ExternalError::Timeout(_))
+        ));
+        assert_eq!(solver.try_guess(&[]).unwrap(), "tares");
+        std::fs::remove_dir(&dir).unwrap();
+
+        let mut solver = bot("exit 0\n").unwrap();
+        assert!(solver.try_guess(&[]).is_err());
+
+        assert!(ExternalSolver::spawn("", Duration::from_secs(1)).is_err());
+        assert!(ExternalSolver::spawn("./no-such-bot", Duration::from_secs(1)).is_err());
+    }
+}
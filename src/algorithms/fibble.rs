@@ -0,0 +1,135 @@
+use super::{Bucket, GuessEvaluation, HighestEntropyAlgorithm, Opening, Prior, Utils, WithOpener};
+use crate::{Attempt, Solver};
+use std::collections::{BTreeMap, HashMap};
+
+// Maximum entropy over the words that fit every row with exactly `lies` wrong tiles
+#[derive(Debug, Clone, PartialEq)]
+pub struct FibbleSolver {
+    pub available_options: BTreeMap<&'static str, u32>,
+    /// Every word the answer can be, `available_options` is filtered from it
+    pub lexicon: BTreeMap<&'static str, u32>,
+    /// The prior used to weight each word as a possible answer
+    pub prior: Prior,
+    /// Weight of every word in the lexicon according to `prior`
+    pub weights: HashMap<&'static str, f64>,
+    /// Number of tiles of every feedback row that have the wrong color
+    pub lies: usize,
+    /// The first guess of every game
+    pub opening: Opening,
+}
+
+impl Default for FibbleSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FibbleSolver {
+    pub fn new() -> Self {
+        Self::with_prior(Prior::Uniform)
+    }
+
+    pub fn with_prior(prior: Prior) -> Self {
+        let lexicon = Utils::word_frequencies();
+        let weights = prior.weights(&lexicon);
+
+        Self {
+            available_options: lexicon.clone(),
+            lexicon,
+            prior,
+            weights,
+            lies: 1,
+            opening: Opening::new(Some("tares")),
+        }
+    }
+
+    /// Makes the solver expect that many lies per row instead of one
+    pub fn with_lies(mut self, lies: usize) -> Result<Self, String> {
+        if lies > 5 {
+            return Err(format!("{} lies do not fit in a row of 5 tiles", lies));
+        }
+        self.lies = lies;
+        Ok(self)
+    }
+
+    /// Keeps the words whose feedback differs from every row on exactly
+    /// `lies` tiles, a word already guessed would have ended the game
+    pub fn update_possible_answers(&mut self, history: &[Attempt]) {
+        let lies = self.lies;
+        self.available_options = self
+            .lexicon
+            .iter()
+            .filter(|(&word, _)| {
+                history
+                    .iter()
+                    .all(|attempt| attempt.word != word && Utils::mismatches(attempt, word) == lies)
+            })
+            .map(|(&word, &frequency)| (word, frequency))
+            .collect();
+    }
+}
+
+impl WithOpener for FibbleSolver {
+    fn opening(&mut self) -> (&mut Opening, &BTreeMap<&'static str, u32>) {
+        (&mut self.opening, &self.lexicon)
+    }
+}
+
+impl Solver for FibbleSolver {
+    fn name(&self) -> &str {
+        "fibble"
+    }
+
+    fn reset(&mut self) {
+        self.available_options = self.lexicon.clone();
+    }
+
+    fn rank(&mut self, history: &[Attempt], n: usize) -> Vec<GuessEvaluation> {
+        self.update_possible_answers(history);
+
+        // the buckets are those of the true feedback, each lie spreads a word
+        // over other patterns but does not change how well a guess splits
+        let (available_options, weights) = (&self.available_options, &self.weights);
+        if history.is_empty() {
+            return self.opening.rank(
+                n,
+                |opener| HighestEntropyAlgorithm::evaluate(opener, available_options, weights),
+                || HighestEntropyAlgorithm::rank_all(available_options, weights),
+            );
+        }
+
+        let mut evaluations = HighestEntropyAlgorithm::rank_all(available_options, weights);
+        evaluations.truncate(n);
+        evaluations
+    }
+
+    fn buckets(&mut self, history: &[Attempt], guess: &str) -> Option<Vec<Bucket>> {
+        self.update_possible_answers(history);
+        Some(HighestEntropyAlgorithm::buckets(
+            guess,
+            &self.available_options,
+            &self.weights,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_fit_every_row_with_the_lies() {
+        // cigar gives IMMII to tares, the row lies on the last tile
+        let history: Vec<Attempt> = vec!["tares:IMMIC".parse().unwrap()];
+        let mut solver = FibbleSolver::new();
+        solver.update_possible_answers(&history);
+        assert!(solver.available_options.contains_key("cigar"));
+        assert!(!solver.available_options.contains_key("tares"));
+
+        // the strict rule of the other solvers rejects the answer
+        let mut solver = solver.with_lies(0).unwrap();
+        solver.update_possible_answers(&history);
+        assert!(!solver.available_options.contains_key("cigar"));
+        assert!(FibbleSolver::new().with_lies(6).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use super::Constraints;
+use crate::{Attempt, Score};
+use std::{borrow::Cow, collections::BTreeMap};
+
+/// Keeps the candidates of a solver in line with the history it is asked
+/// about, so the answer of a solver only depends on the history it is given
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryCache {
+    /// Every word the solver knows about with its n-gram count
+    pub lexicon: BTreeMap<&'static str, u32>,
+    /// The attempts that were already applied to the candidates, in order
+    applied: Vec<Attempt<'static>>,
+}
+
+impl HistoryCache {
+    pub fn new(lexicon: BTreeMap<&'static str, u32>) -> Self {
+        Self {
+            lexicon,
+            applied: Vec::new(),
+        }
+    }
+
+    /// Number of attempts the candidates were filtered with
+    pub fn len(&self) -> usize {
+        self.applied.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty()
+    }
+
+    /// Filters the candidates with the attempts of `history` that were not
+    /// applied yet. If `history` does not continue the one seen so far, the
+    /// candidates are rebuilt from the lexicon.
+    pub fn sync(
+        &mut self,
+        history: &[Attempt],
+        available_options: &mut BTreeMap<&'static str, u32>,
+    ) {
+        let continues = history.len() >= self.applied.len()
+            && self.applied.iter().zip(history).all(|(a, b)| a == b);
+
+        if !continues {
+            self.reset(available_options);
+        }
+
+        let new_attempts = &history[self.applied.len()..];
+        if new_attempts.is_empty() {
+            return;
+        }
+
+        let constraints = Constraints::from_attempts(new_attempts);
+        available_options.retain(|word, _| constraints.matches(word));
+
+        for attempt in new_attempts {
+            // a guess that was not the answer can be dropped right away
+            if attempt.score != [Score::Correct; 5] {
+                available_options.remove(attempt.word.as_ref());
+            }
+            self.applied.push(Attempt {
+                word: Cow::Owned(attempt.word.to_string()),
+                score: attempt.score,
+            });
+        }
+    }
+
+    /// Everything the applied attempts revealed about the answer
+    pub fn constraints(&self) -> Constraints {
+        Constraints::from_attempts(&self.applied)
+    }
+
+    /// Forgets every attempt and makes the whole lexicon available again
+    pub fn reset(&mut self, available_options: &mut BTreeMap<&'static str, u32>) {
+        self.applied.clear();
+        *available_options = self.lexicon.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::Utils;
+
+    fn attempt(word: &'static str, answer: &str) -> Attempt<'static> {
+        Attempt {
+            word: Cow::Borrowed(word),
+            score: Score::color(answer, word),
+        }
+    }
+
+    fn filtered(history: &[Attempt]) -> BTreeMap<&'static str, u32> {
+        let mut available_options = Utils::word_frequencies();
+        for attempt in history {
+            available_options.remove(attempt.word.as_ref());
+            available_options.retain(|word, _| Utils::if_attempt_is_similar_to_word(attempt, word));
+        }
+        available_options
+    }
+
+    #[test]
+    fn test_sync_applies_the_whole_history_at_once() {
+        let history = [attempt("tares", "wrung"), attempt("droid", "wrung")];
+        let mut cache = HistoryCache::new(Utils::word_frequencies());
+        let mut available_options = cache.lexicon.clone();
+
+        cache.sync(&history, &mut available_options);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(available_options, filtered(&history));
+        assert!(available_options.contains_key("wrung"));
+        assert_eq!(cache.constraints(), Constraints::from_attempts(&history));
+    }
+
+    #[test]
+    fn test_sync_only_applies_new_attempts() {
+        let history = [attempt("tares", "wrung"), attempt("droid", "wrung")];
+        let mut cache = HistoryCache::new(Utils::word_frequencies());
+        let mut available_options = cache.lexicon.clone();
+
+        cache.sync(&history[..1], &mut available_options);
+        assert_eq!(available_options, filtered(&history[..1]));
+
+        // words dropped from the options stay dropped when the history grows
+        available_options.remove("wrung");
+        cache.sync(&history, &mut available_options);
+        assert!(!available_options.contains_key("wrung"));
+    }
+
+    #[test]
+    fn test_sync_rebuilds_on_a_different_history() {
+        let first = [attempt("tares", "wrung"), attempt("droid", "wrung")];
+        let second = [attempt("crane", "wrung")];
+        let mut cache = HistoryCache::new(Utils::word_frequencies());
+        let mut available_options = cache.lexicon.clone();
+
+        cache.sync(&first, &mut available_options);
+        cache.sync(&second, &mut available_options);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(available_options, filtered(&second));
+
+        cache.sync(&[], &mut available_options);
+        assert_eq!(available_options.len(), 12972);
+    }
+}
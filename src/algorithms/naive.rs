@@ -1,65 +1,124 @@
-use super::Utils;
+use super::{
+    Bucket, GuessEvaluation, HighestEntropyAlgorithm, HistoryCache, Opening, Prior, Utils,
+    WithOpener,
+};
 use crate::{Attempt, Solver};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct NaiveAlgorithm {
-    pub available_options: HashMap<&'static str, u32>,
-    pub possibility_grid: [[bool; 26]; 5],
-    pub restriction: [bool; 5],
+    pub available_options: BTreeMap<&'static str, u32>,
+    /// The prior used to weight each word as a possible answer
+    pub prior: Prior,
+    /// Weight of every word in the lexicon according to `prior`
+    pub weights: HashMap<&'static str, f64>,
+    /// The attempts `available_options` was filtered with
+    pub history: HistoryCache,
+    /// The first guess of every game
+    pub opening: Opening,
+}
+
+impl Default for NaiveAlgorithm {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl NaiveAlgorithm {
     pub fn new() -> Self {
-        let n_gram_frequency = include_str!("./../../dataset/wordle_words_x_n_gram.txt");
-        let available_options = n_gram_frequency
-            .lines()
-            .map(|line| {
-                let mut parts = line.split_whitespace();
-                let word = parts.next().unwrap();
-                let freq = parts.next().unwrap().parse().unwrap();
-                (word, freq)
-            })
-            .collect();
+        Self::with_prior(Prior::Raw)
+    }
+
+    pub fn with_prior(prior: Prior) -> Self {
+        let available_options = Utils::word_frequencies();
+        let weights = prior.weights(&available_options);
+        let history = HistoryCache::new(available_options.clone());
 
-        let possibility_grid = [[true; 26]; 5];
-        let restriction = [false; 5];
         Self {
             available_options,
-            possibility_grid,
-            restriction,
+            prior,
+            weights,
+            history,
+            opening: Opening::new(None),
         }
     }
 
-    pub fn update_possible_answers(&mut self, last_attempt: Option<&Attempt>) {
-        if last_attempt.is_none() {
-            return;
-        }
-        let history = last_attempt.unwrap();
-        let last_word = history.word.as_ref();
+    pub fn update_possible_answers(&mut self, history: &[Attempt]) {
+        self.history.sync(history, &mut self.available_options);
+    }
 
-        // Remove the last word from the available options
-        // since it is not the correct answer
+    /// The `n` heaviest candidates, fully evaluated
+    fn ranking(&self, n: usize) -> Vec<GuessEvaluation> {
+        let weight = |word: &'static str| self.weights.get(word).copied().unwrap_or(0.0);
+        let mut possible_ans: Vec<_> = self
+            .available_options
+            .iter()
+            .map(|(&word, &frequency)| {
+                let ranked = GuessEvaluation {
+                    score: weight(word),
+                    is_candidate: true,
+                    frequency,
+                    ..GuessEvaluation::unscored(word)
+                };
+                (word, ranked)
+            })
+            .collect();
+        possible_ans.sort_by(|a, b| a.1.cmp_rank(&b.1));
 
-        self.available_options.remove(last_word);
-        self.available_options.retain(|word, _| {
-            return Utils::if_attempt_is_similar_to_word(history, word);
-        });
+        // only the words that make the cut are worth the full evaluation
+        possible_ans
+            .into_iter()
+            .take(n)
+            .map(|(word, ranked)| {
+                let mut evaluation =
+                    HighestEntropyAlgorithm::evaluate(word, &self.available_options, &self.weights);
+                evaluation.score = ranked.score;
+                evaluation
+            })
+            .collect()
+    }
+}
+
+impl WithOpener for NaiveAlgorithm {
+    fn opening(&mut self) -> (&mut Opening, &BTreeMap<&'static str, u32>) {
+        (&mut self.opening, &self.history.lexicon)
     }
 }
 
 impl Solver for NaiveAlgorithm {
-    fn solve(&mut self, history: &[Attempt]) -> String {
-        self.update_possible_answers(history.last());
-        let possible_ans = &self
-            .available_options
-            .iter()
-            .max_by_key(|&(_, count)| count)
-            .unwrap()
-            .0;
+    fn name(&self) -> &str {
+        "naive"
+    }
+
+    fn reset(&mut self) {
+        self.history.reset(&mut self.available_options);
+    }
+
+    fn rank(&mut self, history: &[Attempt], n: usize) -> Vec<GuessEvaluation> {
+        self.update_possible_answers(history);
+
+        let ranking = self.ranking(n);
+        if history.is_empty() {
+            return self.opening.lead(ranking, n, |opener| {
+                let mut evaluation = HighestEntropyAlgorithm::evaluate(
+                    opener,
+                    &self.available_options,
+                    &self.weights,
+                );
+                evaluation.score = self.weights.get(opener).copied().unwrap_or(0.0);
+                evaluation
+            });
+        }
+        ranking
+    }
 
-        // removing the word from the available options as it is already used
-        return possible_ans.to_string();
+    fn buckets(&mut self, history: &[Attempt], guess: &str) -> Option<Vec<Bucket>> {
+        self.update_possible_answers(history);
+        Some(HighestEntropyAlgorithm::buckets(
+            guess,
+            &self.available_options,
+            &self.weights,
+        ))
     }
 }
 
@@ -73,9 +132,7 @@ mod tests {
     #[test]
     fn test_naive_algorithm_solve() {
         let mut naive_algorithm = NaiveAlgorithm::new();
-        naive_algorithm.update_possible_answers(None);
-        assert_eq!(naive_algorithm.possibility_grid, [[true; 26]; 5]);
-        assert_eq!(naive_algorithm.restriction, [false; 5]);
+        naive_algorithm.update_possible_answers(&[]);
         assert_eq!(naive_algorithm.available_options.len(), 12972);
 
         let attempt = Attempt {
@@ -89,17 +146,21 @@ mod tests {
             ],
         };
 
-        naive_algorithm.update_possible_answers(Some(&attempt));
+        naive_algorithm.update_possible_answers(&[attempt]);
+        assert!(naive_algorithm
+            .available_options
+            .keys()
+            .all(|word| word.contains('h') && word.contains('c') && !word.contains('w')));
         assert_eq!(
-            naive_algorithm.restriction,
-            [false, false, false, false, false]
+            naive_algorithm.history.constraints().to_string(),
+            "?????, needs c/h, no i/w"
         );
     }
 
     // #[test]
     // fn test_naive_algorithm_update_possible_answers() {
     //     let mut naive_algorithm = NaiveAlgorithm::new();
-    //     naive_algorithm.update_possible_answers(None);
+    //     naive_algorithm.update_possible_answers(&[]);
     //     assert_eq!(naive_algorithm.possibility_grid, [[true; 26]; 5]);
     //     assert_eq!(naive_algorithm.restriction, [false; 5]);
     //     assert_eq!(naive_algorithm.available_options.len(), 12972);
@@ -133,6 +194,71 @@ mod tests {
     //     assert_eq!(naive_algorithm.available_options.len(), 2);
     // }
 
+    #[test]
+    fn test_naive_algorithm_picks_the_heaviest_word() {
+        // "years" is far more common but can never be the word of the day
+        let mut naive_algorithm = NaiveAlgorithm::with_prior(Prior::Answers);
+        naive_algorithm.available_options = [("years", 0), ("aback", 0)].into();
+        assert_eq!(naive_algorithm.solve(&[]), "aback");
+
+        let mut naive_algorithm = NaiveAlgorithm::new();
+        naive_algorithm.available_options = [("years", 0), ("aback", 0)].into();
+        assert_eq!(naive_algorithm.solve(&[]), "years");
+    }
+
+    #[test]
+    fn test_naive_algorithm_rank() {
+        let mut naive_algorithm = NaiveAlgorithm::new();
+        naive_algorithm.available_options = [("years", 0), ("aback", 0), ("which", 0)].into();
+
+        let ranked = naive_algorithm.rank(&[], 2);
+        let words: Vec<_> = ranked.iter().map(|e| e.word.as_ref()).collect();
+        assert_eq!(words, ["which", "years"]);
+        assert_eq!(ranked[0].worst_case, 1);
+        assert!(ranked[0].is_candidate);
+    }
+
+    #[test]
+    fn test_naive_algorithm_is_a_function_of_the_history() {
+        let history = [
+            Attempt {
+                word: Cow::Borrowed("which"),
+                score: Score::color("sound", "which"),
+            },
+            Attempt {
+                word: Cow::Borrowed("there"),
+                score: Score::color("sound", "there"),
+            },
+        ];
+
+        let mut played_along = NaiveAlgorithm::new();
+        played_along.solve(&history[..1]);
+        let mut joined_mid_game = NaiveAlgorithm::new();
+
+        assert_eq!(
+            played_along.solve(&history),
+            joined_mid_game.solve(&history)
+        );
+        assert_eq!(
+            played_along.available_options,
+            joined_mid_game.available_options
+        );
+
+        // asking about an unrelated history again starts over
+        assert_eq!(played_along.solve(&[]), NaiveAlgorithm::new().solve(&[]));
+    }
+
+    #[test]
+    fn test_naive_algorithm_opener() {
+        let mut naive_algorithm = NaiveAlgorithm::new().with_opener("crane").unwrap();
+        assert_eq!(naive_algorithm.solve(&[]), "crane");
+        naive_algorithm.available_options = [("years", 0), ("crane", 0), ("which", 0)].into();
+        let ranked = naive_algorithm.rank(&[], 3);
+        let words: Vec<_> = ranked.iter().map(|e| e.word.as_ref()).collect();
+        assert_eq!(words, ["crane", "which", "years"]);
+        assert!(NaiveAlgorithm::new().with_opener("xxxxx").is_err());
+    }
+
     #[test]
     fn test_naive_algorithm_initialization() {
         let naive_algorithm = NaiveAlgorithm::new();
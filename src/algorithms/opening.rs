@@ -0,0 +1,98 @@
+use super::GuessEvaluation;
+use std::collections::BTreeMap;
+
+/// The first guess of a solver, its own default or the opener it was told to
+/// play instead
+#[derive(Debug, Clone, PartialEq)]
+pub struct Opening {
+    default: Option<&'static str>,
+    opener: Option<&'static str>,
+    /// Every guess ranked against the whole lexicon, the same every game
+    ranking: Option<Vec<GuessEvaluation>>,
+}
+
+impl Opening {
+    /// Opens with `default`, none when the solver picks its first guess the
+    /// way it picks any other
+    pub fn new(default: Option<&'static str>) -> Self {
+        Self {
+            default,
+            opener: None,
+            ranking: None,
+        }
+    }
+
+    /// The word every game opens with, if there is one
+    pub fn word(&self) -> Option<&'static str> {
+        self.opener.or(self.default)
+    }
+
+    /// Opens every game with the word, which has to be one of the lexicon
+    pub fn set(
+        &mut self,
+        opener: &str,
+        lexicon: &BTreeMap<&'static str, u32>,
+    ) -> Result<(), String> {
+        match lexicon.get_key_value(opener) {
+            Some((&opener, _)) => {
+                self.opener = Some(opener);
+                Ok(())
+            }
+            None => Err(format!("'{}' is not an allowed guess", opener)),
+        }
+    }
+
+    /// Puts the opener ahead of the ranking, the `n` best guesses of the first
+    /// move
+    pub fn lead(
+        &self,
+        ranking: impl IntoIterator<Item = GuessEvaluation>,
+        n: usize,
+        evaluate: impl FnOnce(&'static str) -> GuessEvaluation,
+    ) -> Vec<GuessEvaluation> {
+        let opener = self.word();
+        opener
+            .map(evaluate)
+            .into_iter()
+            .chain(
+                ranking
+                    .into_iter()
+                    .filter(|evaluation| Some(evaluation.word.as_ref()) != opener),
+            )
+            .take(n)
+            .collect()
+    }
+
+    /// The `n` best guesses of the first move. One guess is the opener alone,
+    /// more rank every guess against the whole lexicon with `rank_all`. That
+    /// takes a while, so it is only done once per solver.
+    pub fn rank(
+        &mut self,
+        n: usize,
+        evaluate: impl FnOnce(&'static str) -> GuessEvaluation,
+        rank_all: impl FnOnce() -> Vec<GuessEvaluation>,
+    ) -> Vec<GuessEvaluation> {
+        if n <= 1 && self.word().is_some() {
+            return self.lead([], n, evaluate);
+        }
+        if self.ranking.is_none() {
+            self.ranking = Some(rank_all());
+        }
+        let ranking = self.ranking.as_deref().unwrap_or_default();
+        self.lead(ranking.iter().cloned(), n, evaluate)
+    }
+}
+
+/// A solver that can be told which word to open every game with
+pub trait WithOpener: Sized {
+    /// The opening of the solver along with the words it may guess
+    fn opening(&mut self) -> (&mut Opening, &BTreeMap<&'static str, u32>);
+
+    /// Makes the solver open every game with the word instead of its default
+    /// opener
+    fn with_opener(mut self, opener: &str) -> Result<Self, String> {
+        let (opening, lexicon) = self.opening();
+        opening.set(opener, lexicon)?;
+        Ok(self)
+    }
+}
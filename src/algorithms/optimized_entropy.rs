@@ -1,10 +1,12 @@
-use rand::Rng;
-
-use super::HighestEntropyAlgorithm;
-use crate::{Attempt, Score, Solver};
-use std::collections::HashMap;
+use super::{
+    Bucket, GuessEvaluation, HighestEntropyAlgorithm, HistoryCache, Opening, Prior, Utils,
+    WithOpener,
+};
+use crate::{Attempt, Solver};
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct WordScore {
     pub word: &'static str,
     pub score: f64,
@@ -17,103 +19,161 @@ impl WordScore {
 }
 
 // Maximum entropy calculation
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct OptimizedEntropyAlgorithm {
-    pub available_options: HashMap<&'static str, u32>,
+    pub available_options: BTreeMap<&'static str, u32>,
+    /// The prior used to weight each word as a possible answer
+    pub prior: Prior,
+    /// Weight of every word in the lexicon according to `prior`
+    pub weights: HashMap<&'static str, f64>,
+    /// The attempts `available_options` was filtered with
+    pub history: HistoryCache,
+    /// The first guess of every game
+    pub opening: Opening,
+}
+
+impl Default for OptimizedEntropyAlgorithm {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl OptimizedEntropyAlgorithm {
     pub fn new() -> Self {
-        let n_gram_frequency = include_str!("./../../dataset/wordle_words_x_n_gram.txt");
-        let available_options = n_gram_frequency
-            .lines()
-            .map(|line| {
-                let mut parts = line.split_whitespace();
-                let word = parts.next().unwrap();
-                let freq = parts.next().unwrap().parse().unwrap();
-                (word, freq)
-            })
-            .collect();
-
-        Self { available_options }
+        Self::with_prior(Prior::Raw)
+    }
+
+    pub fn with_prior(prior: Prior) -> Self {
+        let available_options = Utils::word_frequencies();
+        let weights = prior.weights(&available_options);
+        let history = HistoryCache::new(available_options.clone());
+
+        Self {
+            available_options,
+            prior,
+            weights,
+            history,
+            opening: Opening::new(Some("tared")),
+        }
     }
 
     pub fn calculate_score(
         word: &'static str,
-        available_options: HashMap<&'static str, u32>,
+        available_options: BTreeMap<&'static str, u32>,
+        weights: &HashMap<&'static str, f64>,
     ) -> WordScore {
-        let mut entropy = 0.0;
-        let count = available_options.len();
-        let total_freq: i64 = available_options.iter().map(|(_, &v)| v as i64).sum();
-        let frequency = *available_options.get(word).unwrap_or(&0) as f64 / total_freq as f64;
-
-        // Calculate the entropy of the word
-        // using the formula: -p * log2(p)
-        // where p is the probability of the word
-
-        for possibility in Score::permutations() {
-            let mut possible_options = available_options.clone();
-
-            let attempt = Attempt {
-                word: std::borrow::Cow::Borrowed(word),
-                score: possibility,
-            };
-
-            let count_after_chosing = HighestEntropyAlgorithm::fetch_length_for_updated_dictionary(
-                &attempt,
-                &mut possible_options,
-            ) as f64;
+        let total_mass = Prior::mass(available_options.keys(), weights);
+        let evaluation =
+            OptimizedEntropyAlgorithm::evaluate(word, &available_options, weights, total_mass);
+        WordScore::new(word, evaluation.score)
+    }
 
-            if count_after_chosing == 0.0 {
-                continue;
-            }
+    /// Rewards the information a guess gives plus twice the frequency of the
+    /// word among the available options, weighted by the prior. Nothing is
+    /// added when the options carry no weight.
+    pub fn score(evaluation: &GuessEvaluation, weight: f64, total_mass: f64) -> f64 {
+        let frequency = if total_mass > 0.0 && evaluation.is_candidate {
+            weight / total_mass
+        } else {
+            0.0
+        };
+        evaluation.entropy + frequency * 2f64
+    }
 
-            let probability = count_after_chosing / count as f64;
+    fn evaluate(
+        word: &'static str,
+        available_options: &BTreeMap<&'static str, u32>,
+        weights: &HashMap<&'static str, f64>,
+        total_mass: f64,
+    ) -> GuessEvaluation {
+        let mut evaluation = HighestEntropyAlgorithm::evaluate(word, available_options, weights);
+        let weight = weights.get(word).copied().unwrap_or(0.0);
+        evaluation.score = OptimizedEntropyAlgorithm::score(&evaluation, weight, total_mass);
+        evaluation
+    }
 
-            entropy += (probability * probability.log2()) * -1 as f64;
-        }
+    /// Evaluates every available option as a guess, the best first
+    fn rank_all(
+        available_options: &BTreeMap<&'static str, u32>,
+        weights: &HashMap<&'static str, f64>,
+    ) -> Vec<GuessEvaluation> {
+        let words: Vec<&'static str> = available_options.keys().copied().collect();
+        let total_mass = Prior::mass(available_options.keys(), weights);
+        let mut evaluations = GuessEvaluation::evaluate_all(&words, |word| {
+            OptimizedEntropyAlgorithm::evaluate(word, available_options, weights, total_mass)
+        });
+        GuessEvaluation::sort(&mut evaluations);
+        evaluations
+    }
+}
 
-        WordScore::new(word, entropy + frequency * 2f64)
+impl WithOpener for OptimizedEntropyAlgorithm {
+    fn opening(&mut self) -> (&mut Opening, &BTreeMap<&'static str, u32>) {
+        (&mut self.opening, &self.history.lexicon)
     }
 }
 
 impl Solver for OptimizedEntropyAlgorithm {
-    fn solve(&mut self, history: &[Attempt]) -> String {
-        let last_attempt = history.last();
+    fn name(&self) -> &str {
+        "optimized"
+    }
 
-        // used to reduce the time on first attempt
-        if last_attempt.is_none() {
-            let starter_words = vec!["tared", "crane", "whale"];
-            return starter_words[rand::thread_rng().gen_range(0..3)].to_string();
-        }
+    fn reset(&mut self) {
+        self.history.reset(&mut self.available_options);
+    }
 
-        let last_try = last_attempt.unwrap();
-        let last_word = last_try.word.as_ref();
-
-        // println!("Last {:?}", last_try);
-
-        self.available_options.remove(last_word);
-        HighestEntropyAlgorithm::fetch_length_for_updated_dictionary(
-            &last_try,
-            &mut self.available_options,
-        );
-        let mut best_word = None;
-
-        for (word, _) in self.available_options.iter() {
-            let word_entropy =
-                OptimizedEntropyAlgorithm::calculate_score(*word, self.available_options.clone());
-
-            if best_word.is_none() {
-                best_word = Some(word_entropy);
-            } else {
-                let best_word = best_word.as_mut().unwrap();
-                if word_entropy.score > best_word.score {
-                    *best_word = word_entropy;
-                }
-            }
+    fn rank(&mut self, history: &[Attempt], n: usize) -> Vec<GuessEvaluation> {
+        self.history.sync(history, &mut self.available_options);
+
+        let (available_options, weights) = (&self.available_options, &self.weights);
+        if history.is_empty() {
+            let total_mass = Prior::mass(available_options.keys(), weights);
+            return self.opening.rank(
+                n,
+                |opener| {
+                    OptimizedEntropyAlgorithm::evaluate(
+                        opener,
+                        available_options,
+                        weights,
+                        total_mass,
+                    )
+                },
+                || OptimizedEntropyAlgorithm::rank_all(available_options, weights),
+            );
         }
 
-        let result = best_word.unwrap().word.to_string();
-        return result;
+        let mut evaluations = OptimizedEntropyAlgorithm::rank_all(available_options, weights);
+        evaluations.truncate(n);
+        evaluations
+    }
+
+    fn buckets(&mut self, history: &[Attempt], guess: &str) -> Option<Vec<Bucket>> {
+        self.history.sync(history, &mut self.available_options);
+        Some(HighestEntropyAlgorithm::buckets(
+            guess,
+            &self.available_options,
+            &self.weights,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_adds_the_weighted_frequency() {
+        let options: BTreeMap<&'static str, u32> = [("cigar", 3), ("rebut", 1)].into();
+        let weights = Prior::Raw.weights(&options);
+        let score = OptimizedEntropyAlgorithm::calculate_score("cigar", options.clone(), &weights);
+        let evaluation = HighestEntropyAlgorithm::evaluate("cigar", &options, &weights);
+        assert_eq!(score.score, evaluation.entropy + 2.0 * 3.0 / 4.0);
+
+        // options without weight add nothing, even if each is as likely
+        let weights = HashMap::new();
+        let score = OptimizedEntropyAlgorithm::calculate_score("cigar", options.clone(), &weights);
+        let evaluation = HighestEntropyAlgorithm::evaluate("cigar", &options, &weights);
+        assert_eq!(evaluation.win_probability, 0.5);
+        assert_eq!(score.score, evaluation.entropy);
     }
 }
@@ -0,0 +1,149 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    str::FromStr,
+};
+
+/// How the n-gram counts of the lexicon are turned into the probability of a
+/// word being the answer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Prior {
+    /// Every word is equally likely
+    Uniform,
+    /// The probability grows linearly with the raw n-gram count
+    Raw,
+    /// A sigmoid over the log of the frequency rank, common words get a
+    /// weight close to 1 and rare words a weight close to 0
+    Sigmoid {
+        /// Rank at which the weight drops to 0.5
+        midpoint: f64,
+        /// How sharp the drop around the midpoint is
+        steepness: f64,
+    },
+    /// 1 for the words that can be the word of the day (`ans.txt`), 0 otherwise
+    Answers,
+}
+
+impl Prior {
+    /// The sigmoid prior with defaults tuned to the size of the answer list
+    pub fn sigmoid() -> Self {
+        Prior::Sigmoid {
+            midpoint: 3000.0,
+            steepness: 4.0,
+        }
+    }
+
+    /// Computes the (unnormalized) weight of every word in the lexicon
+    pub fn weights(&self, frequencies: &BTreeMap<&'static str, u32>) -> HashMap<&'static str, f64> {
+        match *self {
+            Prior::Uniform => frequencies.keys().map(|&word| (word, 1.0)).collect(),
+            Prior::Raw => frequencies
+                .iter()
+                .map(|(&word, &count)| (word, count as f64))
+                .collect(),
+            Prior::Sigmoid {
+                midpoint,
+                steepness,
+            } => {
+                // most frequent word first, ties broken alphabetically so the ranks are stable
+                let mut ranked: Vec<_> = frequencies.iter().collect();
+                ranked.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+                ranked
+                    .into_iter()
+                    .enumerate()
+                    .map(|(rank, (&word, _))| {
+                        let x = ((rank + 1) as f64).ln() - midpoint.ln();
+                        (word, 1.0 / (1.0 + (steepness * x).exp()))
+                    })
+                    .collect()
+            }
+            Prior::Answers => {
+                let answers: std::collections::HashSet<&str> =
+                    include_str!("./../../dataset/wordle/ans.txt")
+                        .lines()
+                        .map(|word| word.trim())
+                        .collect();
+
+                frequencies
+                    .keys()
+                    .map(|&word| (word, if answers.contains(word) { 1.0 } else { 0.0 }))
+                    .collect()
+            }
+        }
+    }
+
+    /// Sums the weights of the given words, words without a weight count as 0
+    pub fn mass<'a>(
+        words: impl Iterator<Item = &'a &'static str>,
+        weights: &HashMap<&'static str, f64>,
+    ) -> f64 {
+        words
+            .map(|word| weights.get(word).copied().unwrap_or(0.0))
+            .sum()
+    }
+}
+
+impl fmt::Display for Prior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Prior::Uniform => write!(f, "uniform"),
+            Prior::Raw => write!(f, "raw"),
+            Prior::Sigmoid { .. } => write!(f, "sigmoid"),
+            Prior::Answers => write!(f, "answers"),
+        }
+    }
+}
+
+impl FromStr for Prior {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "uniform" => Ok(Prior::Uniform),
+            "raw" => Ok(Prior::Raw),
+            "sigmoid" => Ok(Prior::sigmoid()),
+            "answers" => Ok(Prior::Answers),
+            _ => Err(format!(
+                "unknown prior '{}', expected one of: uniform, raw, sigmoid, answers",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::Utils;
+
+    #[test]
+    fn test_uniform_prior_gives_every_word_the_same_weight() {
+        let weights = Prior::Uniform.weights(&Utils::word_frequencies());
+        assert_eq!(weights.len(), 12972);
+        assert!(weights.values().all(|&w| w == 1.0));
+    }
+
+    #[test]
+    fn test_sigmoid_prior_prefers_common_words() {
+        let weights = Prior::sigmoid().weights(&Utils::word_frequencies());
+        assert!(weights["which"] > 0.99);
+        assert!(weights["aalii"] < 0.01);
+        assert!(weights.values().all(|&w| w > 0.0 && w < 1.0));
+    }
+
+    #[test]
+    fn test_answers_prior_is_an_indicator() {
+        let weights = Prior::Answers.weights(&Utils::word_frequencies());
+        assert_eq!(weights["aback"], 1.0);
+        assert_eq!(weights["aahed"], 0.0);
+        assert_eq!(weights.values().sum::<f64>(), 2315.0);
+    }
+
+    #[test]
+    fn test_parse_prior() {
+        assert_eq!("Raw".parse(), Ok(Prior::Raw));
+        assert_eq!("sigmoid".parse(), Ok(Prior::sigmoid()));
+        assert!("zipf".parse::<Prior>().is_err());
+    }
+}
@@ -0,0 +1,337 @@
+#[cfg(not(target_arch = "wasm32"))]
+use super::ExternalSolver;
+use super::{
+    FibbleSolver, HighestEntropyAlgorithm, NaiveAlgorithm, OptimizedEntropyAlgorithm, Prior,
+    WithOpener,
+};
+use crate::Solver;
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// A solver by name with its options, written `name` or
+/// `name:key=value,key=value`, e.g. `entropy:opener=crane,prior=sigmoid`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolverSpec {
+    pub name: String,
+    pub options: BTreeMap<String, String>,
+}
+
+impl SolverSpec {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            options: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the option unless the spec already has it
+    pub fn with_default(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.options
+            .entry(key.to_string())
+            .or_insert_with(|| value.into());
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.options.get(key).map(String::as_str)
+    }
+
+    /// Fails on the first option that is not one of `keys`
+    pub fn only(&self, keys: &[&str]) -> Result<(), String> {
+        match self
+            .options
+            .keys()
+            .find(|key| !keys.contains(&key.as_str()))
+        {
+            Some(key) => Err(format!(
+                "unknown option '{}' for solver '{}', expected one of: {}",
+                key,
+                self.name,
+                keys.join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for SolverSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for (i, (key, value)) in self.options.iter().enumerate() {
+            let separator = if i == 0 { ':' } else { ',' };
+            write!(f, "{}{}={}", separator, key, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SolverSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, options) = s.split_once(':').unwrap_or((s, ""));
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("missing solver name in '{}'", s));
+        }
+
+        let mut spec = SolverSpec::new(name.to_ascii_lowercase());
+        for option in options.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+            let Some((key, value)) = option.split_once('=') else {
+                return Err(format!("option '{}' should look like key=value", option));
+            };
+            let key = key.trim().to_ascii_lowercase();
+            if spec.options.insert(key, value.trim().to_string()).is_some() {
+                return Err(format!("option '{}' is given twice", option));
+            }
+        }
+        Ok(spec)
+    }
+}
+
+type Factory = Box<dyn Fn(&SolverSpec) -> Result<Box<dyn Solver>, String> + Send + Sync>;
+
+/// Builds solvers from a `SolverSpec`, so a new algorithm only has to be
+/// registered here to be usable everywhere a solver is picked by name
+pub struct SolverRegistry {
+    factories: BTreeMap<String, Factory>,
+    aliases: BTreeMap<String, String>,
+}
+
+impl Default for SolverRegistry {
+    /// The registry with every solver of the crate
+    fn default() -> Self {
+        #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+        let mut registry = Self::builtins();
+        #[cfg(not(target_arch = "wasm32"))]
+        registry.register("external", external);
+        registry
+    }
+}
+
+/// The solvers of the crate all take a `prior` and an `opener`
+fn builtin<S: Solver + WithOpener + 'static>(
+    spec: &SolverSpec,
+    new: fn() -> S,
+    with_prior: fn(Prior) -> S,
+) -> Result<Box<dyn Solver>, String> {
+    spec.only(&["prior", "opener"])?;
+
+    let solver = match spec.get("prior") {
+        Some(prior) => with_prior(prior.parse()?),
+        None => new(),
+    };
+    let solver = match spec.get("opener") {
+        Some(opener) => solver.with_opener(opener)?,
+        None => solver,
+    };
+    Ok(Box::new(solver))
+}
+
+/// The solver of `builtin` that also takes the number of `lies` per row
+fn fibble(spec: &SolverSpec) -> Result<Box<dyn Solver>, String> {
+    spec.only(&["prior", "opener", "lies"])?;
+
+    let solver = match spec.get("prior") {
+        Some(prior) => FibbleSolver::with_prior(prior.parse()?),
+        None => FibbleSolver::new(),
+    };
+    let solver = match spec.get("lies") {
+        Some(lies) => match lies.parse() {
+            Ok(lies) => solver.with_lies(lies)?,
+            Err(_) => return Err(format!("invalid lies '{}', expected a number", lies)),
+        },
+        None => solver,
+    };
+    let solver = match spec.get("opener") {
+        Some(opener) => solver.with_opener(opener)?,
+        None => solver,
+    };
+    Ok(Box::new(solver))
+}
+
+/// A program speaking the protocol of `ExternalSolver`, run with `command`
+/// and given `timeout` milliseconds per guess
+#[cfg(not(target_arch = "wasm32"))]
+fn external(spec: &SolverSpec) -> Result<Box<dyn Solver>, String> {
+    spec.only(&["command", "timeout"])?;
+
+    let Some(command) = spec.get("command") else {
+        return Err("the external solver needs a command to run".to_string());
+    };
+    let timeout = match spec.get("timeout") {
+        Some(timeout) => match timeout.parse() {
+            Ok(millis) => std::time::Duration::from_millis(millis),
+            Err(_) => {
+                return Err(format!(
+                    "invalid timeout '{}', expected milliseconds",
+                    timeout
+                ))
+            }
+        },
+        None => ExternalSolver::DEFAULT_TIMEOUT,
+    };
+
+    match ExternalSolver::spawn(command, timeout) {
+        Ok(solver) => Ok(Box::new(solver)),
+        Err(err) => Err(format!("cannot start '{}': {}", command, err)),
+    }
+}
+
+impl SolverRegistry {
+    /// The solvers of the crate that run in this process, without the
+    /// external one, which runs any program it is told to. For specs that
+    /// come from someone else, e.g. over the network.
+    pub fn builtins() -> Self {
+        let mut registry = Self::empty();
+        registry.register("naive", |spec| {
+            builtin(spec, NaiveAlgorithm::new, NaiveAlgorithm::with_prior)
+        });
+        registry.register("entropy", |spec| {
+            builtin(
+                spec,
+                HighestEntropyAlgorithm::new,
+                HighestEntropyAlgorithm::with_prior,
+            )
+        });
+        registry.register("optimized", |spec| {
+            builtin(
+                spec,
+                OptimizedEntropyAlgorithm::new,
+                OptimizedEntropyAlgorithm::with_prior,
+            )
+        });
+        registry.register("fibble", fibble);
+        registry.alias("random", "naive");
+        registry.alias("highest-entropy", "entropy");
+        registry.alias("optimized-entropy", "optimized");
+        registry
+    }
+
+    /// A registry without any solver
+    pub fn empty() -> Self {
+        Self {
+            factories: BTreeMap::new(),
+            aliases: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a solver, replacing the one registered under the same name
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&SolverSpec) -> Result<Box<dyn Solver>, String> + Send + Sync + 'static,
+    {
+        self.factories.insert(name.to_string(), Box::new(factory));
+    }
+
+    /// Makes the solver registered as `name` available as `alias` too
+    pub fn alias(&mut self, alias: &str, name: &str) {
+        self.aliases.insert(alias.to_string(), name.to_string());
+    }
+
+    /// The name the solver is registered under, following aliases
+    pub fn resolve<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+        let name = self.aliases.get(name).map_or(name, String::as_str);
+        self.factories
+            .get_key_value(name)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// The registered names, without the aliases
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+
+    pub fn build(&self, spec: &SolverSpec) -> Result<Box<dyn Solver>, String> {
+        let Some(name) = self.resolve(&spec.name) else {
+            return Err(format!(
+                "unknown solver '{}', expected one of: {}",
+                spec.name,
+                self.names().collect::<Vec<_>>().join(", ")
+            ));
+        };
+        (self.factories[name])(spec)
+    }
+
+    /// Parses the spec and builds the solver
+    pub fn create(&self, spec: &str) -> Result<Box<dyn Solver>, String> {
+        self.build(&spec.parse()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_round_trip() {
+        let spec: SolverSpec = "Entropy:opener=crane, prior=sigmoid".parse().unwrap();
+        assert_eq!(spec.name, "entropy");
+        assert_eq!(spec.get("opener"), Some("crane"));
+        assert_eq!(spec.get("prior"), Some("sigmoid"));
+        assert_eq!(spec.to_string(), "entropy:opener=crane,prior=sigmoid");
+        assert_eq!(spec.to_string().parse::<SolverSpec>().unwrap(), spec);
+
+        assert_eq!(
+            "naive".parse::<SolverSpec>().unwrap(),
+            SolverSpec::new("naive")
+        );
+        assert!("".parse::<SolverSpec>().is_err());
+        assert!("entropy:opener".parse::<SolverSpec>().is_err());
+        assert!("entropy:prior=raw,prior=raw".parse::<SolverSpec>().is_err());
+    }
+
+    #[test]
+    fn test_builds_solvers_by_name_and_alias() {
+        let registry = SolverRegistry::default();
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            ["entropy", "external", "fibble", "naive", "optimized"]
+        );
+
+        for (spec, name) in [
+            ("random", "naive"),
+            ("highest-entropy", "entropy"),
+            ("optimized-entropy:prior=uniform", "optimized"),
+            ("fibble:lies=2", "fibble"),
+        ] {
+            assert_eq!(registry.create(spec).unwrap().name(), name);
+        }
+
+        let mut solver = registry.create("entropy:opener=crane").unwrap();
+        assert_eq!(solver.solve(&[]), "crane");
+    }
+
+    #[test]
+    fn test_rejects_bad_specs() {
+        let registry = SolverRegistry::default();
+        assert!(registry.create("genius").is_err());
+        assert!(registry.create("entropy:opener=zzzzz").is_err());
+        assert!(registry.create("entropy:prior=loud").is_err());
+        assert!(registry.create("entropy:depth=2").is_err());
+        assert!(registry.create("entropy:lies=1").is_err());
+        assert!(registry.create("fibble:lies=many").is_err());
+        assert!(registry.create("fibble:lies=6").is_err());
+        assert!(registry.create("external").is_err());
+        assert!(registry.create("external:command=./no-such-bot").is_err());
+        assert!(registry
+            .create("external:command=cat,timeout=soon")
+            .is_err());
+        assert!(SolverRegistry::builtins()
+            .create("external:command=cat")
+            .is_err());
+    }
+
+    #[test]
+    fn test_register_a_new_solver() {
+        let mut registry = SolverRegistry::empty();
+        registry.register("first", |spec| {
+            spec.only(&[])?;
+            let solver: fn(&[crate::Attempt]) -> String = |_| "crane".to_string();
+            Ok(Box::new(solver))
+        });
+
+        let mut solver = registry.create("first").unwrap();
+        assert_eq!(solver.solve(&[]), "crane");
+        assert!(registry.create("first:opener=slate").is_err());
+    }
+}
@@ -0,0 +1,363 @@
+use super::{Bucket, GuessEvaluation, Utils};
+use crate::{Attempt, Score, Solver};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The best second guess for every feedback a fixed opener can get, so
+/// simulated games do not have to work it out again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecondGuessTable {
+    pub opener: String,
+    /// Hash of the lexicon the table was built with, see `SecondGuessTable::lexicon_hash`
+    pub lexicon_hash: u64,
+    /// Name and configuration of the solver that picked the guesses
+    pub algorithm: String,
+    /// The second guess by `Score::index` of the feedback, `None` when no word
+    /// of the lexicon gives that feedback
+    guesses: Vec<Option<String>>,
+}
+
+impl SecondGuessTable {
+    /// Version of the file format. It is part of the cache key along with the
+    /// version of the crate, whose solvers may pick other guesses.
+    pub const VERSION: u32 = 2;
+
+    /// Asks the solver for its second guess after every feedback the opener
+    /// can get on the lexicon
+    pub fn build<S: Solver>(
+        opener: &str,
+        lexicon: &BTreeMap<&'static str, u32>,
+        algorithm: &str,
+        mut solver: S,
+    ) -> Self {
+        let mut possible = [false; 243];
+        for word in lexicon.keys() {
+            possible[Score::index(&Score::color(word, opener))] = true;
+        }
+
+        let guesses = possible
+            .iter()
+            .enumerate()
+            .map(|(index, &possible)| {
+                let score = Score::from_index(index);
+                // nothing to guess once the opener was the answer
+                if !possible || score == [Score::Correct; 5] {
+                    return None;
+                }
+
+                let history = [Attempt {
+                    word: Cow::Borrowed(opener),
+                    score,
+                }];
+                Some(solver.solve(&history))
+            })
+            .collect();
+
+        Self {
+            opener: opener.to_string(),
+            lexicon_hash: SecondGuessTable::lexicon_hash(lexicon),
+            algorithm: algorithm.to_string(),
+            guesses,
+        }
+    }
+
+    /// The second guess after the opener got the feedback
+    pub fn get(&self, score: &[Score; 5]) -> Option<&str> {
+        self.guesses[Score::index(score)].as_deref()
+    }
+
+    /// A hash of the words and their counts that stays the same across runs
+    /// and platforms (FNV-1a)
+    pub fn lexicon_hash(lexicon: &BTreeMap<&'static str, u32>) -> u64 {
+        Utils::fnv1a(
+            lexicon
+                .iter()
+                .flat_map(|(word, count)| format!("{} {}\n", word, count).into_bytes()),
+        )
+    }
+
+    /// The first line of the table file, everything the table is valid for
+    fn header(opener: &str, lexicon_hash: u64, algorithm: &str) -> String {
+        format!(
+            "# version {} wordl {} opener {} algorithm {} lexicon {:016x}",
+            SecondGuessTable::VERSION,
+            env!("CARGO_PKG_VERSION"),
+            opener,
+            algorithm,
+            lexicon_hash
+        )
+    }
+
+    /// Where the table for the key is stored inside the cache directory
+    pub fn path(
+        dir: impl AsRef<Path>,
+        opener: &str,
+        lexicon_hash: u64,
+        algorithm: &str,
+    ) -> PathBuf {
+        dir.as_ref().join(format!(
+            "second-guess-v{}-{}-{}-{}-{:016x}.txt",
+            SecondGuessTable::VERSION,
+            env!("CARGO_PKG_VERSION"),
+            algorithm,
+            opener,
+            lexicon_hash
+        ))
+    }
+
+    pub fn save(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        fs::create_dir_all(&dir)?;
+
+        let mut contents =
+            SecondGuessTable::header(&self.opener, self.lexicon_hash, &self.algorithm);
+        contents.push('\n');
+        for (index, guess) in self.guesses.iter().enumerate() {
+            if let Some(guess) = guess {
+                let pattern = Score::to_pattern(&Score::from_index(index));
+                contents.push_str(&format!("{} {}\n", pattern, guess));
+            }
+        }
+
+        let path = SecondGuessTable::path(dir, &self.opener, self.lexicon_hash, &self.algorithm);
+        fs::write(path, contents)
+    }
+
+    /// Reads the table for the key from the cache directory, `None` if it was
+    /// never saved or its header is not the one of the key, as in a file
+    /// renamed or written by another version
+    pub fn load(
+        dir: impl AsRef<Path>,
+        opener: &str,
+        lexicon_hash: u64,
+        algorithm: &str,
+    ) -> io::Result<Option<Self>> {
+        let path = SecondGuessTable::path(dir, opener, lexicon_hash, algorithm);
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        if contents.lines().next()
+            != Some(&SecondGuessTable::header(opener, lexicon_hash, algorithm))
+        {
+            return Ok(None);
+        }
+
+        let mut guesses = vec![None; 243];
+        for line in contents.lines().filter(|line| !line.starts_with('#')) {
+            let invalid = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid line '{}'", line),
+                )
+            };
+            let mut parts = line.split_whitespace();
+            let score = parts
+                .next()
+                .and_then(Score::from_pattern)
+                .ok_or_else(invalid)?;
+            let guess = parts.next().ok_or_else(invalid)?;
+            guesses[Score::index(&score)] = Some(guess.to_string());
+        }
+
+        Ok(Some(Self {
+            opener: opener.to_string(),
+            lexicon_hash,
+            algorithm: algorithm.to_string(),
+            guesses,
+        }))
+    }
+
+    /// Loads the table from the cache directory, or builds and saves it
+    pub fn load_or_build<S: Solver>(
+        dir: impl AsRef<Path>,
+        opener: &str,
+        lexicon: &BTreeMap<&'static str, u32>,
+        algorithm: &str,
+        solver: S,
+    ) -> io::Result<Self> {
+        let lexicon_hash = SecondGuessTable::lexicon_hash(lexicon);
+        if let Some(table) = SecondGuessTable::load(&dir, opener, lexicon_hash, algorithm)? {
+            return Ok(table);
+        }
+
+        let table = SecondGuessTable::build(opener, lexicon, algorithm, solver);
+        table.save(dir)?;
+        Ok(table)
+    }
+}
+
+/// A solver that looks its second guess up in a `SecondGuessTable` when the
+/// game was opened with the opener of the table
+#[derive(Debug)]
+pub struct SecondGuessCache<'a, S> {
+    pub solver: S,
+    pub table: &'a SecondGuessTable,
+}
+
+impl<'a, S: Solver> SecondGuessCache<'a, S> {
+    pub fn new(solver: S, table: &'a SecondGuessTable) -> Self {
+        Self { solver, table }
+    }
+
+    fn lookup(&self, history: &[Attempt]) -> Option<&'a str> {
+        match history {
+            [attempt] if attempt.word == self.table.opener => self.table.get(&attempt.score),
+            _ => None,
+        }
+    }
+}
+
+impl<S: Solver> Solver for SecondGuessCache<'_, S> {
+    fn name(&self) -> &str {
+        self.solver.name()
+    }
+
+    fn reset(&mut self) {
+        self.solver.reset()
+    }
+
+    fn rank(&mut self, history: &[Attempt], n: usize) -> Vec<GuessEvaluation> {
+        let mut ranked = self.solver.rank(history, n);
+        if let Some(guess) = self.lookup(history) {
+            // the guess of the table goes first, scored if the solver still ranks it
+            let evaluation = match ranked
+                .iter()
+                .position(|evaluation| evaluation.word == guess)
+            {
+                Some(position) => ranked.remove(position),
+                None => GuessEvaluation::unscored(guess.to_string()),
+            };
+            ranked.insert(0, evaluation);
+            ranked.truncate(n);
+        }
+        ranked
+    }
+
+    fn try_solve(&mut self, history: &[Attempt]) -> Result<String, String> {
+        match self.lookup(history) {
+            Some(guess) => Ok(guess.to_string()),
+            None => self.solver.try_solve(history),
+        }
+    }
+
+    fn buckets(&mut self, history: &[Attempt], guess: &str) -> Option<Vec<Bucket>> {
+        self.solver.buckets(history, guess)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{HighestEntropyAlgorithm, NaiveAlgorithm, Utils};
+
+    fn lexicon() -> BTreeMap<&'static str, u32> {
+        let answers: Vec<&str> = include_str!("./../../dataset/wordle/ans.txt")
+            .lines()
+            .take(300)
+            .collect();
+        Utils::word_frequencies()
+            .into_iter()
+            .filter(|(word, _)| answers.contains(word))
+            .collect()
+    }
+
+    #[test]
+    fn test_lexicon_hash_is_stable() {
+        let lexicon: BTreeMap<&'static str, u32> = [("aback", 1), ("abase", 2)].into();
+        let reordered: BTreeMap<&'static str, u32> = [("abase", 2), ("aback", 1)].into();
+        assert_eq!(
+            SecondGuessTable::lexicon_hash(&lexicon),
+            SecondGuessTable::lexicon_hash(&reordered)
+        );
+        assert_ne!(
+            SecondGuessTable::lexicon_hash(&lexicon),
+            SecondGuessTable::lexicon_hash(&[("aback", 1)].into())
+        );
+    }
+
+    #[test]
+    fn test_table_matches_the_solver() {
+        let lexicon = lexicon();
+        let mut solver = HighestEntropyAlgorithm::new();
+        let table = SecondGuessTable::build("tares", &lexicon, "entropy", solver.clone());
+
+        let score = Score::color("brain", "tares");
+        let history = [Attempt {
+            word: Cow::Borrowed("tares"),
+            score,
+        }];
+        assert_eq!(table.get(&score), Some(solver.solve(&history).as_str()));
+        assert_eq!(table.get(&[Score::Correct; 5]), None);
+
+        let mut cached = SecondGuessCache::new(HighestEntropyAlgorithm::new(), &table);
+        assert_eq!(cached.solve(&history), table.get(&score).unwrap());
+        assert_eq!(cached.rank(&history, 3)[0].word, table.get(&score).unwrap());
+    }
+
+    #[test]
+    fn test_rank_answers_from_the_table() {
+        let lexicon = lexicon();
+        let table = SecondGuessTable::build("tares", &lexicon, "entropy", NaiveAlgorithm::new());
+        let score = Score::color("brain", "tares");
+        let history = [Attempt {
+            word: Cow::Borrowed("tares"),
+            score,
+        }];
+
+        // a table built by another solver still decides the guess
+        let mut cached = SecondGuessCache::new(HighestEntropyAlgorithm::new(), &table);
+        let guess = cached.solve(&history);
+        assert_eq!(guess, table.get(&score).unwrap());
+        let ranked = cached.rank(&history, 2);
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].word, guess);
+        assert_eq!(cached.explain(&history).unwrap().guess.word, guess);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let lexicon = lexicon();
+        let dir = std::env::temp_dir().join(format!("wordl-second-guess-{}", std::process::id()));
+
+        let table = SecondGuessTable::load_or_build(
+            &dir,
+            "tares",
+            &lexicon,
+            "entropy",
+            HighestEntropyAlgorithm::new(),
+        )
+        .unwrap();
+        let hash = SecondGuessTable::lexicon_hash(&lexicon);
+        assert_eq!(
+            SecondGuessTable::load(&dir, "tares", hash, "entropy").unwrap(),
+            Some(table)
+        );
+        assert_eq!(
+            SecondGuessTable::load(&dir, "crane", hash, "entropy").unwrap(),
+            None
+        );
+
+        // a table saved under another key is built again
+        let path = SecondGuessTable::path(&dir, "tares", hash, "entropy");
+        let stale = fs::read_to_string(&path)
+            .unwrap()
+            .replace("opener tares", "opener crane");
+        fs::write(&path, stale).unwrap();
+        assert_eq!(
+            SecondGuessTable::load(&dir, "tares", hash, "entropy").unwrap(),
+            None
+        );
+        SecondGuessTable::load_or_build(&dir, "tares", &lexicon, "entropy", NaiveAlgorithm::new())
+            .unwrap();
+        assert!(SecondGuessTable::load(&dir, "tares", hash, "entropy")
+            .unwrap()
+            .is_some());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,62 +1,80 @@
+use super::Constraints;
 use crate::{Attempt, Score};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 pub struct Utils;
 
 impl Utils {
-    // function will verify if the word can be a potential candidate or not
+    /// Loads every allowed word along with its n-gram count
+    pub fn word_frequencies() -> BTreeMap<&'static str, u32> {
+        let n_gram_frequency = include_str!("./../../dataset/wordle_words_x_n_gram.txt");
+        n_gram_frequency
+            .lines()
+            .map(|line| {
+                let mut parts = line.split_whitespace();
+                let word = parts.next().unwrap();
+                let freq = parts.next().unwrap().parse().unwrap();
+                (word, freq)
+            })
+            .collect()
+    }
+
+    // function will verify if the word can be a potential candidate or not,
+    // filter many words with the same `Constraints` instead of calling this in a loop
     pub fn if_attempt_is_similar_to_word(attempt: &Attempt, word: &'static str) -> bool {
-        if attempt.word.len() != word.len() {
-            return false;
-        }
+        Constraints::from(attempt).matches(word)
+    }
 
-        let mut word_frequency_character_map = HashMap::new();
+    /// Number of tiles of the attempt that would have another color if the
+    /// word was the answer, 0 when the word fits the attempt
+    pub fn mismatches(attempt: &Attempt, word: &str) -> usize {
+        Score::color(word, &attempt.word)
+            .iter()
+            .zip(&attempt.score)
+            .filter(|(truth, given)| truth != given)
+            .count()
+    }
 
-        for c in word.chars() {
-            *word_frequency_character_map.entry(c).or_insert(0) += 1;
-        }
+    /// FNV-1a of the bytes, a hash that stays the same across builds and
+    /// platforms unlike the std hasher, for seeds and names that are kept
+    pub(crate) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+        bytes.into_iter().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
 
-        let mut attempted_mismatched_character = HashMap::new();
-        let mut attempted_incorrect_character = HashMap::new();
-
-        for (i, (c, sc)) in attempt.word.chars().zip(attempt.score).enumerate() {
-            match sc {
-                Score::Correct => {
-                    if c != word.chars().nth(i).unwrap() {
-                        return false;
-                    }
-                    word_frequency_character_map
-                        .entry(c)
-                        .and_modify(|e| *e -= 1);
-                }
-                Score::Misplaced => {
-                    // if the misplced character is already at the position
-                    if c == word.chars().nth(i).unwrap() {
-                        return false;
-                    }
-                    *attempted_mismatched_character.entry(c).or_insert(0) += 1
-                }
-                Score::Incorrect => *attempted_incorrect_character.entry(c).or_insert(0) += 1,
-            }
-        }
+    /// The 32 bit variant of `fnv1a`, for the lexicon of game records
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    pub(crate) fn fnv1a_32(bytes: impl IntoIterator<Item = u8>) -> u32 {
+        bytes.into_iter().fold(0x811c9dc5, |hash, byte| {
+            (hash ^ byte as u32).wrapping_mul(0x01000193)
+        })
+    }
+}
 
-        for (c, count) in attempted_mismatched_character {
-            *word_frequency_character_map.entry(c).or_insert(0) -= count;
-            if word_frequency_character_map.get(&c) == None
-                || *word_frequency_character_map.get(&c).unwrap() < 0
-            {
-                return false;
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        for (c, _) in attempted_incorrect_character {
-            if word_frequency_character_map.get(&c) != None
-                && *word_frequency_character_map.get(&c).unwrap() > 0
-            {
-                return false;
-            }
-        }
+    #[test]
+    fn test_fnv1a_matches_the_reference() {
+        assert_eq!(Utils::fnv1a(*b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(Utils::fnv1a_32(*b"a"), 0xe40c292c);
+        assert_eq!(Utils::fnv1a([]), 0xcbf29ce484222325);
+    }
 
-        true
+    #[test]
+    fn test_mismatches_agree_with_the_strict_rule() {
+        let attempt: Attempt = "tares:IMMII".parse().unwrap();
+        for word in ["cigar", "sugar", "rebut", "tares", "crane"] {
+            assert_eq!(
+                Utils::mismatches(&attempt, word) == 0,
+                Utils::if_attempt_is_similar_to_word(&attempt, word)
+            );
+        }
+        // the answer cigar gives IMMII, here with a lie on the last tile
+        let attempt: Attempt = "tares:IMMIC".parse().unwrap();
+        assert_eq!(Utils::mismatches(&attempt, "cigar"), 1);
+        assert_eq!(Utils::mismatches(&attempt, "tares"), 4);
     }
 }
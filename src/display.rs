@@ -0,0 +1,73 @@
+//! Feedback drawn as colored tiles on a terminal, with the letters on green,
+//! yellow and grey backgrounds.
+//!
+//! Colors are left out when the `NO_COLOR` environment variable is set, when
+//! `TERM` is `dumb` or when stdout is not a terminal, the attempt is then
+//! written as `tares:IMMII`.
+
+use crate::{Attempt, Score};
+use std::{
+    fmt,
+    io::{self, IsTerminal},
+};
+
+/// Whether stdout should get ANSI colors
+pub fn color_enabled() -> bool {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    let dumb = std::env::var_os("TERM").is_some_and(|term| term == "dumb");
+    !no_color && !dumb && io::stdout().is_terminal()
+}
+
+/// Displays an attempt as tiles, or as plain text without colors
+pub struct Tiles<'a, 'b> {
+    attempt: &'a Attempt<'b>,
+    color: bool,
+}
+
+impl<'a, 'b> Tiles<'a, 'b> {
+    pub fn new(attempt: &'a Attempt<'b>, color: bool) -> Self {
+        Self { attempt, color }
+    }
+}
+
+impl fmt::Display for Tiles<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.color {
+            return write!(f, "{}", self.attempt);
+        }
+        for (c, score) in self.attempt.word.chars().zip(self.attempt.score) {
+            // black letters on a green, yellow or grey background
+            let background = match score {
+                Score::Correct => 42,
+                Score::Misplaced => 43,
+                Score::Incorrect => 100,
+            };
+            write!(
+                f,
+                "\x1b[1;30;{}m {} \x1b[0m",
+                background,
+                c.to_ascii_uppercase()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_tiles() {
+        let attempt = Attempt {
+            word: Cow::Borrowed("tares"),
+            score: Score::color("cigar", "tares"),
+        };
+        assert_eq!(Tiles::new(&attempt, false).to_string(), "tares:IMMII");
+
+        let tiles = Tiles::new(&attempt, true).to_string();
+        assert!(tiles.starts_with("\x1b[1;30;100m T \x1b[0m\x1b[1;30;43m A \x1b[0m"));
+        assert_eq!(tiles.matches("\x1b[0m").count(), 5);
+    }
+}
@@ -0,0 +1,465 @@
+//! C API to embed the solvers, the header is `include/wordl.h`. The build
+//! generates it again with the `ffi` feature, copy it over with
+//! `WORDL_UPDATE_HEADER=1 cargo test --features ffi header`.
+//!
+//! Every function returns a `WordlStatus` instead of panicking, and reports
+//! its results through out pointers. Strings are NUL terminated UTF-8,
+//! words are written to buffers of at least `WORDL_WORD_BUFFER` bytes.
+//!
+//! ```c
+//! WordlLexicon *lexicon = wordl_lexicon_new();
+//! WordlSolver *solver = NULL;
+//! wordl_solver_new(lexicon, "entropy:prior=sigmoid", &solver);
+//! wordl_solver_push(solver, "tares", "IMMII");
+//! char guess[WORDL_WORD_BUFFER];
+//! wordl_solver_next_guess(solver, guess, sizeof guess);
+//! wordl_solver_free(solver);
+//! wordl_lexicon_free(lexicon);
+//! ```
+
+use crate::{
+    algorithms::{SolverRegistry, Utils},
+    referee::Referee,
+    Attempt, Score, Solver,
+};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    ffi::{c_char, CStr},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+    sync::Arc,
+};
+
+/// Size of a buffer holding a word and its NUL
+pub const WORDL_WORD_BUFFER: usize = 6;
+
+/// What a function of the C API did
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordlStatus {
+    Ok = 0,
+    /// A pointer argument was NULL
+    NullPointer = 1,
+    /// A string argument was not UTF-8
+    InvalidUtf8 = 2,
+    /// The word is not in the lexicon
+    InvalidWord = 3,
+    /// The pattern is not five of C, M and I
+    InvalidPattern = 4,
+    /// No solver goes by that spec
+    UnknownSolver = 5,
+    /// No word fits the attempts pushed so far
+    NoGuess = 6,
+    /// The output buffer is too small
+    BufferTooSmall = 7,
+    /// Something went wrong inside the library
+    Internal = 8,
+}
+
+/// The words of the game, with their n-gram counts. The solvers load the
+/// same words on their own, the lexicon is only there to look words up and
+/// to check the attempts pushed to a solver.
+pub struct WordlLexicon {
+    words: Arc<BTreeMap<&'static str, u32>>,
+}
+
+/// A solver and the attempts of the game it is helping with
+pub struct WordlSolver {
+    solver: Box<dyn Solver>,
+    lexicon: Arc<BTreeMap<&'static str, u32>>,
+    history: Vec<Attempt<'static>>,
+}
+
+/// A guess ranked by a solver
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct WordlSuggestion {
+    pub word: [c_char; WORDL_WORD_BUFFER],
+    pub score: f64,
+    pub entropy: f64,
+    pub expected_remaining: f64,
+    pub worst_case: usize,
+    pub win_probability: f64,
+    pub is_candidate: bool,
+}
+
+/// Runs the body, turning a panic into `WordlStatus::Internal` so it never
+/// unwinds into C
+fn guard(body: impl FnOnce() -> Result<(), WordlStatus>) -> WordlStatus {
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => WordlStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => WordlStatus::Internal,
+    }
+}
+
+/// # Safety
+/// `s` is NULL or a NUL terminated string
+unsafe fn to_str<'a>(s: *const c_char) -> Result<&'a str, WordlStatus> {
+    if s.is_null() {
+        return Err(WordlStatus::NullPointer);
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| WordlStatus::InvalidUtf8)
+}
+
+/// # Safety
+/// `pointer` is NULL or valid for `T`
+unsafe fn to_mut<'a, T>(pointer: *mut T) -> Result<&'a mut T, WordlStatus> {
+    pointer.as_mut().ok_or(WordlStatus::NullPointer)
+}
+
+fn write_word(word: &str, out: &mut [c_char]) -> Result<(), WordlStatus> {
+    if out.len() < word.len() + 1 {
+        return Err(WordlStatus::BufferTooSmall);
+    }
+    for (i, byte) in word.bytes().enumerate() {
+        out[i] = byte as c_char;
+    }
+    out[word.len()] = 0;
+    Ok(())
+}
+
+/// A short description of the status, a static string that must not be freed
+#[no_mangle]
+pub extern "C" fn wordl_status_message(status: WordlStatus) -> *const c_char {
+    let message: &'static CStr = match status {
+        WordlStatus::Ok => c"ok",
+        WordlStatus::NullPointer => c"a pointer argument is NULL",
+        WordlStatus::InvalidUtf8 => c"a string argument is not UTF-8",
+        WordlStatus::InvalidWord => c"the word is not in the lexicon",
+        WordlStatus::InvalidPattern => c"the pattern is not five of C, M and I",
+        WordlStatus::UnknownSolver => c"no solver goes by that spec",
+        WordlStatus::NoGuess => c"no word fits the attempts",
+        WordlStatus::BufferTooSmall => c"the output buffer is too small",
+        WordlStatus::Internal => c"internal error",
+    };
+    message.as_ptr()
+}
+
+/// Writes the pattern the guess gets against the answer, e.g. `IMMII`, to
+/// `out`
+///
+/// # Safety
+/// `answer` and `guess` are NUL terminated strings and `out` has room for
+/// `out_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn wordl_color(
+    answer: *const c_char,
+    guess: *const c_char,
+    out: *mut c_char,
+    out_len: usize,
+) -> WordlStatus {
+    guard(|| {
+        let (answer, guess) = (to_str(answer)?, to_str(guess)?);
+        if out.is_null() {
+            return Err(WordlStatus::NullPointer);
+        }
+        let word = |word| Referee::normalize(word).map_err(|_| WordlStatus::InvalidWord);
+        let (answer, guess) = (word(answer)?, word(guess)?);
+
+        let pattern = Score::to_pattern(&Score::color(&answer, &guess));
+        write_word(&pattern, std::slice::from_raw_parts_mut(out, out_len))
+    })
+}
+
+/// The lexicon embedded in the library, free it with `wordl_lexicon_free`
+#[no_mangle]
+pub extern "C" fn wordl_lexicon_new() -> *mut WordlLexicon {
+    match panic::catch_unwind(Utils::word_frequencies) {
+        Ok(words) => Box::into_raw(Box::new(WordlLexicon {
+            words: Arc::new(words),
+        })),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// # Safety
+/// `lexicon` is NULL or was returned by `wordl_lexicon_new` and not freed yet
+#[no_mangle]
+pub unsafe extern "C" fn wordl_lexicon_free(lexicon: *mut WordlLexicon) {
+    if !lexicon.is_null() {
+        drop(Box::from_raw(lexicon));
+    }
+}
+
+/// Number of words in the lexicon
+///
+/// # Safety
+/// `lexicon` is NULL or a live lexicon
+#[no_mangle]
+pub unsafe extern "C" fn wordl_lexicon_len(lexicon: *const WordlLexicon) -> usize {
+    lexicon.as_ref().map_or(0, |lexicon| lexicon.words.len())
+}
+
+/// Whether the word is in the lexicon
+///
+/// # Safety
+/// `lexicon` is NULL or a live lexicon, `word` is NULL or a NUL terminated
+/// string
+#[no_mangle]
+pub unsafe extern "C" fn wordl_lexicon_contains(
+    lexicon: *const WordlLexicon,
+    word: *const c_char,
+) -> bool {
+    match (lexicon.as_ref(), to_str(word)) {
+        (Some(lexicon), Ok(word)) => lexicon.words.contains_key(word),
+        _ => false,
+    }
+}
+
+/// Creates the solver of the spec, e.g. `entropy` or `naive:prior=raw`, and
+/// stores it in `out`. The lexicon is what `wordl_solver_push` checks words
+/// against, it can be freed before the solver.
+///
+/// # Safety
+/// `lexicon` is a live lexicon, `spec` a NUL terminated string and `out`
+/// valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn wordl_solver_new(
+    lexicon: *const WordlLexicon,
+    spec: *const c_char,
+    out: *mut *mut WordlSolver,
+) -> WordlStatus {
+    guard(|| {
+        let lexicon = lexicon.as_ref().ok_or(WordlStatus::NullPointer)?;
+        let spec = to_str(spec)?;
+        let out = to_mut(out)?;
+
+        let solver = SolverRegistry::default()
+            .create(spec)
+            .map_err(|_| WordlStatus::UnknownSolver)?;
+        *out = Box::into_raw(Box::new(WordlSolver {
+            solver,
+            lexicon: Arc::clone(&lexicon.words),
+            history: Vec::new(),
+        }));
+        Ok(())
+    })
+}
+
+/// # Safety
+/// `solver` is NULL or was created by `wordl_solver_new` and not freed yet
+#[no_mangle]
+pub unsafe extern "C" fn wordl_solver_free(solver: *mut WordlSolver) {
+    if !solver.is_null() {
+        // a solver that panics while dropping must not unwind into C
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(solver))));
+    }
+}
+
+/// Forgets the attempts to start a new game
+///
+/// # Safety
+/// `solver` is NULL or a live solver
+#[no_mangle]
+pub unsafe extern "C" fn wordl_solver_reset(solver: *mut WordlSolver) -> WordlStatus {
+    guard(|| {
+        let solver = to_mut(solver)?;
+        solver.history.clear();
+        solver.solver.reset();
+        Ok(())
+    })
+}
+
+/// Adds an attempt of the game, the word and its pattern, e.g. `IMMII`
+///
+/// # Safety
+/// `solver` is NULL or a live solver, `word` and `pattern` are NULL or NUL
+/// terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn wordl_solver_push(
+    solver: *mut WordlSolver,
+    word: *const c_char,
+    pattern: *const c_char,
+) -> WordlStatus {
+    guard(|| {
+        let solver = to_mut(solver)?;
+        let word = to_str(word)?.to_ascii_lowercase();
+        let pattern = to_str(pattern)?.to_ascii_uppercase();
+
+        if !solver.lexicon.contains_key(word.as_str()) {
+            return Err(WordlStatus::InvalidWord);
+        }
+        let score = Score::from_pattern(&pattern).ok_or(WordlStatus::InvalidPattern)?;
+        solver.history.push(Attempt {
+            word: Cow::Owned(word),
+            score,
+        });
+        Ok(())
+    })
+}
+
+/// Number of attempts pushed since the solver was created or reset
+///
+/// # Safety
+/// `solver` is NULL or a live solver
+#[no_mangle]
+pub unsafe extern "C" fn wordl_solver_attempts(solver: *const WordlSolver) -> usize {
+    solver.as_ref().map_or(0, |solver| solver.history.len())
+}
+
+/// Writes the next guess of the solver to `out`
+///
+/// # Safety
+/// `solver` is NULL or a live solver and `out` has room for `out_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn wordl_solver_next_guess(
+    solver: *mut WordlSolver,
+    out: *mut c_char,
+    out_len: usize,
+) -> WordlStatus {
+    guard(|| {
+        let solver = to_mut(solver)?;
+        if out.is_null() {
+            return Err(WordlStatus::NullPointer);
+        }
+
+        let ranked = solver.solver.rank(&solver.history, 1);
+        let guess = ranked.first().ok_or(WordlStatus::NoGuess)?;
+        write_word(&guess.word, std::slice::from_raw_parts_mut(out, out_len))
+    })
+}
+
+/// Writes up to `capacity` of the best next guesses to `out`, best first, and
+/// their number to `count`
+///
+/// # Safety
+/// `solver` is NULL or a live solver, `out` has room for `capacity`
+/// suggestions and `count` is valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn wordl_solver_suggestions(
+    solver: *mut WordlSolver,
+    out: *mut WordlSuggestion,
+    capacity: usize,
+    count: *mut usize,
+) -> WordlStatus {
+    guard(|| {
+        let solver = to_mut(solver)?;
+        let count = to_mut(count)?;
+        if out.is_null() {
+            return Err(WordlStatus::NullPointer);
+        }
+
+        let ranked = solver.solver.rank(&solver.history, capacity);
+        for (i, evaluation) in ranked.iter().take(capacity).enumerate() {
+            let mut word = [0; WORDL_WORD_BUFFER];
+            write_word(&evaluation.word, &mut word)?;
+            out.add(i).write(WordlSuggestion {
+                word,
+                score: evaluation.score,
+                entropy: evaluation.entropy,
+                expected_remaining: evaluation.expected_remaining,
+                worst_case: evaluation.worst_case,
+                win_probability: evaluation.win_probability,
+                is_candidate: evaluation.is_candidate,
+            });
+        }
+        *count = ranked.len().min(capacity);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(buffer: &[c_char]) -> String {
+        unsafe { CStr::from_ptr(buffer.as_ptr()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    /// The committed header has to be the one the build generates
+    #[test]
+    fn test_header_is_up_to_date() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/wordl.h"));
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/wordl.h");
+        if std::env::var_os("WORDL_UPDATE_HEADER").is_some() {
+            std::fs::write(path, generated).unwrap();
+        }
+        assert!(
+            std::fs::read_to_string(path).unwrap() == generated,
+            "include/wordl.h is stale, run `WORDL_UPDATE_HEADER=1 cargo test --features ffi header`"
+        );
+    }
+
+    #[test]
+    fn test_color() {
+        let mut out = [0; WORDL_WORD_BUFFER];
+        unsafe {
+            let status = wordl_color(c"cigar".as_ptr(), c"tares".as_ptr(), out.as_mut_ptr(), 6);
+            assert_eq!(status, WordlStatus::Ok);
+            assert_eq!(word(&out), "IMMII");
+
+            let status = wordl_color(c"cigar".as_ptr(), c"tar".as_ptr(), out.as_mut_ptr(), 6);
+            assert_eq!(status, WordlStatus::InvalidWord);
+            let status = wordl_color(c"cigar".as_ptr(), c"tares".as_ptr(), out.as_mut_ptr(), 5);
+            assert_eq!(status, WordlStatus::BufferTooSmall);
+            let status = wordl_color(ptr::null(), c"tares".as_ptr(), out.as_mut_ptr(), 6);
+            assert_eq!(status, WordlStatus::NullPointer);
+        }
+    }
+
+    #[test]
+    fn test_solver_lifecycle() {
+        unsafe {
+            let lexicon = wordl_lexicon_new();
+            assert_eq!(wordl_lexicon_len(lexicon), 12972);
+            assert!(wordl_lexicon_contains(lexicon, c"cigar".as_ptr()));
+
+            let mut solver = ptr::null_mut();
+            let status = wordl_solver_new(lexicon, c"genius".as_ptr(), &mut solver);
+            assert_eq!(status, WordlStatus::UnknownSolver);
+            let status = wordl_solver_new(lexicon, c"entropy".as_ptr(), &mut solver);
+            assert_eq!(status, WordlStatus::Ok);
+            // the solver keeps the words it needs
+            wordl_lexicon_free(lexicon);
+
+            let push = |word: &CStr, pattern: &CStr| {
+                wordl_solver_push(solver, word.as_ptr(), pattern.as_ptr())
+            };
+            assert_eq!(push(c"xyzzy", c"IIIII"), WordlStatus::InvalidWord);
+            assert_eq!(push(c"tares", c"IMXII"), WordlStatus::InvalidPattern);
+            assert_eq!(push(c"tares", c"IMMII"), WordlStatus::Ok);
+            assert_eq!(push(c"grail", c"MMMMI"), WordlStatus::Ok);
+            assert_eq!(wordl_solver_attempts(solver), 2);
+
+            let mut guess = [0; WORDL_WORD_BUFFER];
+            let status = wordl_solver_next_guess(solver, guess.as_mut_ptr(), guess.len());
+            assert_eq!(status, WordlStatus::Ok);
+
+            let mut suggestions = [WordlSuggestion {
+                word: [0; WORDL_WORD_BUFFER],
+                score: 0.0,
+                entropy: 0.0,
+                expected_remaining: 0.0,
+                worst_case: 0,
+                win_probability: 0.0,
+                is_candidate: false,
+            }; 3];
+            let mut count = 0;
+            let status = wordl_solver_suggestions(solver, suggestions.as_mut_ptr(), 3, &mut count);
+            assert_eq!(status, WordlStatus::Ok);
+            assert!(count > 0 && count <= 3);
+            assert_eq!(word(&suggestions[0].word), word(&guess));
+
+            assert_eq!(push(c"cigar", c"IIIII"), WordlStatus::Ok);
+            let status = wordl_solver_next_guess(solver, guess.as_mut_ptr(), guess.len());
+            assert_eq!(status, WordlStatus::NoGuess);
+
+            assert_eq!(wordl_solver_reset(solver), WordlStatus::Ok);
+            assert_eq!(wordl_solver_attempts(solver), 0);
+            wordl_solver_free(solver);
+        }
+    }
+
+    #[test]
+    fn test_status_messages() {
+        let message = unsafe { CStr::from_ptr(wordl_status_message(WordlStatus::NoGuess)) };
+        assert_eq!(message.to_str().unwrap(), "no word fits the attempts");
+    }
+}
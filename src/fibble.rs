@@ -0,0 +1,134 @@
+//! Fibble, the variant where the host lies: every feedback row has exactly
+//! `lies` tiles with the wrong color, and the player is not told which.
+//!
+//! A lie never repeats the true color of its tile, so a row with one lie
+//! differs from the real feedback on exactly one tile. The game is won when
+//! the guess is the answer, that last row is the only one told truthfully.
+
+use crate::{algorithms::Utils, Attempt, Score, Solver};
+use rand::{rngs::StdRng, seq::index, Rng, SeedableRng};
+use std::borrow::Cow;
+
+/// Hosts Fibble games, the lies are picked by a seeded generator so a game
+/// can be played again with the same feedback
+#[derive(Debug, Clone)]
+pub struct Fibble {
+    lies: usize,
+    rng: StdRng,
+}
+
+impl Fibble {
+    /// One lie per row, as in the original game
+    pub const DEFAULT_LIES: usize = 1;
+
+    /// The guesses a player gets, one lie per row needs a few more than six
+    pub const MAX_GUESSES: usize = 9;
+
+    pub fn new(lies: usize, seed: u64) -> Result<Self, String> {
+        if lies > 5 {
+            return Err(format!("{} lies do not fit in a row of 5 tiles", lies));
+        }
+        Ok(Self {
+            lies,
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+
+    /// A host seeded with the answer, so a benchmark gets the same lies on
+    /// every run whatever order it plays the answers in
+    pub fn for_answer(lies: usize, answer: &str) -> Result<Self, String> {
+        Fibble::new(lies, Utils::fnv1a(answer.bytes()))
+    }
+
+    pub fn lies(&self) -> usize {
+        self.lies
+    }
+
+    /// The feedback of the guess with `lies` tiles changed to another color,
+    /// the truth when the guess is the answer
+    pub fn feedback(&mut self, answer: &str, guess: &str) -> [Score; 5] {
+        let mut score = Score::color(answer, guess);
+        if guess == answer {
+            return score;
+        }
+        for tile in index::sample(&mut self.rng, 5, self.lies) {
+            let others: Vec<Score> = [Score::Correct, Score::Misplaced, Score::Incorrect]
+                .into_iter()
+                .filter(|&other| other != score[tile])
+                .collect();
+            score[tile] = others[self.rng.gen_range(0..others.len())];
+        }
+        score
+    }
+
+    /// Plays a whole game without printing anything, the history ends with
+    /// the attempt that found the answer. Fails when the solver has no guess
+    /// left to make or runs out of guesses.
+    pub fn simulate<S: Solver>(
+        &mut self,
+        answer: &'static str,
+        mut solver: S,
+        max_guesses: usize,
+    ) -> Result<Vec<Attempt<'static>>, String> {
+        let mut game_history = Vec::new();
+        while game_history.len() < max_guesses {
+            let guess = solver.try_solve(&game_history)?;
+            let score = self.feedback(answer, &guess);
+            let finished = guess == answer;
+
+            game_history.push(Attempt {
+                word: Cow::Owned(guess),
+                score,
+            });
+
+            if finished {
+                return Ok(game_history);
+            }
+        }
+        Err(format!("no answer after {} guesses", max_guesses))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::FibbleSolver;
+
+    #[test]
+    fn test_every_row_has_exactly_the_lies() {
+        for lies in 0..=5 {
+            let mut fibble = Fibble::new(lies, 7).unwrap();
+            for guess in ["tares", "crane", "sugar", "pilot"] {
+                let attempt = Attempt {
+                    word: Cow::Borrowed(guess),
+                    score: fibble.feedback("cigar", guess),
+                };
+                assert_eq!(Utils::mismatches(&attempt, "cigar"), lies);
+            }
+            assert_eq!(fibble.feedback("cigar", "cigar"), [Score::Correct; 5]);
+        }
+        assert!(Fibble::new(6, 7).is_err());
+    }
+
+    #[test]
+    fn test_the_same_seed_tells_the_same_lies() {
+        let feedback = |mut fibble: Fibble| fibble.feedback("cigar", "tares");
+        assert_eq!(
+            feedback(Fibble::for_answer(1, "cigar").unwrap()),
+            feedback(Fibble::for_answer(1, "cigar").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_solver_finds_the_answer_despite_the_lies() {
+        let mut fibble = Fibble::for_answer(1, "cigar").unwrap();
+        let history = fibble.simulate("cigar", FibbleSolver::new(), 20).unwrap();
+        assert_eq!(history.last().unwrap().word, "cigar");
+
+        // no word fits rows that lie on every tile about the answer
+        let mut solver = FibbleSolver::new().with_lies(5).unwrap();
+        solver.lexicon = [("cigar", 1)].into();
+        let mut fibble = Fibble::new(0, 7).unwrap();
+        assert!(fibble.simulate("cigar", solver, 20).is_err());
+    }
+}
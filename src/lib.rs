@@ -1,7 +1,32 @@
 pub mod algorithms;
+pub mod display;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fibble;
+pub mod openers;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "serde")]
+pub mod record;
+pub mod referee;
+pub mod schedule;
+pub mod search;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod stats;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+use algorithms::GuessEvaluation;
+use algorithms::{Bucket, Constraints, Explanation};
 use std::{
     borrow::Cow,
     collections::{hash_map, HashSet},
+    fmt,
+    str::FromStr,
 };
 
 pub struct Wordle {
@@ -9,6 +34,12 @@ pub struct Wordle {
     dictionary: HashSet<&'static str>,
 }
 
+impl Default for Wordle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Wordle {
     pub fn new() -> Self {
         let dictionary = include_str!("../dataset/combined_ans_guess.txt")
@@ -19,16 +50,35 @@ impl Wordle {
         Self { dictionary }
     }
 
-    pub fn assist<S: Solver>(&self, mut solver: S) -> () {
+    /// Suggests guesses for a game played somewhere else, reading the
+    /// feedback from stdin
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn assist<S: Solver>(&self, mut solver: S, explain: bool) {
         let mut game_history = Vec::new();
+        let color = display::color_enabled();
         println!("--------------------------------------------");
         println!("               Wordle Assistant             ");
         println!("--------------------------------------------");
 
         loop {
-            let guess = solver.solve(&game_history);
+            // the explanation ranks the guesses already, its guess is the one to play
+            let explanation = if explain {
+                solver.explain(&game_history)
+            } else {
+                None
+            };
+            let guess = match &explanation {
+                Some(explanation) => explanation.guess.word.to_string(),
+                None => solver.solve(&game_history),
+            };
             println!("My suggestion is to use '{}'", guess);
 
+            match explanation {
+                Some(explanation) => println!("{}", explanation),
+                None if explain => println!("This solver cannot explain its choice"),
+                None => {}
+            }
+
             println!("How was that ? (C for correct, M for misplaced, I for incorrect)");
             let mut performance = String::new();
             std::io::stdin()
@@ -39,22 +89,16 @@ impl Wordle {
 
             assert!(performance.len() == 5);
 
-            let mut score = [Score::Correct; 5];
-            for (i, ch) in performance.to_string().chars().enumerate() {
-                let score_performance = match ch {
-                    'C' => Score::Correct,
-                    'M' => Score::Misplaced,
-                    'I' => Score::Incorrect,
-                    _ => panic!("Invalid input"),
-                };
-                score[i] = score_performance;
-            }
+            let score = Score::from_pattern(&performance).expect("Invalid input");
 
             assert!(self.dictionary.contains(&*guess));
             game_history.push(Attempt {
                 word: Cow::Owned(guess),
                 score,
             });
+            for attempt in &game_history {
+                println!("  {}", display::Tiles::new(attempt, color));
+            }
 
             if score.iter().all(|&x| x == Score::Correct) {
                 println!(
@@ -63,34 +107,70 @@ impl Wordle {
                 );
                 break;
             }
+
+            println!(
+                "What we know so far: {}",
+                Constraints::from_attempts(&game_history)
+            );
         }
     }
 
-    pub fn game<S: Solver>(&self, answer: &'static str, mut solver: S) -> Result<usize, ()> {
-        let mut game_history = Vec::new();
+    #[allow(clippy::result_unit_err)]
+    pub fn game<S: Solver>(&self, answer: &'static str, solver: S) -> Result<usize, ()> {
+        let game_history = self
+            .simulate(answer, solver, Wordle::MAX_SIMULATED_GUESSES)
+            .map_err(|err| println!(" The game stopped: {}", err))?;
+        Wordle::print_game(&game_history);
+        Ok(game_history.len())
+    }
+
+    /// Prints the guesses of a game on one line, with their colors
+    pub fn print_game(game_history: &[Attempt]) {
+        let color = display::color_enabled();
         print!(" Guess");
-        loop {
-            let guess = solver.solve(&game_history);
-            print!(" -> {}", guess);
-            if guess == answer {
-                print!(" -> Finished !\n");
-                return Ok(game_history.len() + 1);
-            }
+        for attempt in game_history {
+            print!(" -> {}", display::Tiles::new(attempt, color));
+        }
+        println!(" -> Finished !");
+    }
 
-            debug_assert!(self.dictionary.contains(&*guess));
+    /// The guesses a simulated game gets unless told otherwise, far more than
+    /// the solvers of this crate need, so it only stops a solver that is stuck
+    pub const MAX_SIMULATED_GUESSES: usize = 20;
+
+    /// Plays a whole game without printing anything, the history ends with
+    /// the attempt that found the answer. Fails when the solver has no guess,
+    /// guesses a word that is not in the dictionary or runs out of guesses.
+    pub fn simulate<S: Solver>(
+        &self,
+        answer: &'static str,
+        mut solver: S,
+        max_guesses: usize,
+    ) -> Result<Vec<Attempt<'static>>, String> {
+        let mut game_history = Vec::new();
+        while game_history.len() < max_guesses {
+            let guess = solver.try_solve(&game_history)?;
             let score = Score::color(answer, &guess);
-            // println!("Score: {:?}", score);
+            let finished = score == [Score::Correct; 5];
+            if !finished && !self.dictionary.contains(guess.as_str()) {
+                return Err(format!("'{}' is not in the dictionary", guess));
+            }
 
             game_history.push(Attempt {
                 word: Cow::Owned(guess),
                 score,
             });
+
+            if finished {
+                return Ok(game_history);
+            }
         }
+        Err(format!("no answer after {} guesses", max_guesses))
     }
 }
 
 /// A struct that represents a single attempt to guess the word
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Attempt<'a> {
     /// The word that was guessed in a perticular attempt
     pub word: Cow<'a, str>,
@@ -99,6 +179,31 @@ pub struct Attempt<'a> {
     pub score: [Score; 5],
 }
 
+/// Writes the attempt as the word and its pattern, e.g. `tares:IMMII`
+impl fmt::Display for Attempt<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.word, Score::to_pattern(&self.score))
+    }
+}
+
+impl FromStr for Attempt<'static> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((word, pattern)) = s.trim().split_once(':') else {
+            return Err(format!("'{}' should look like word:PATTERN", s));
+        };
+        let word = referee::Referee::normalize(word)?;
+        let score = Score::from_pattern(&pattern.to_ascii_uppercase())
+            .ok_or_else(|| format!("'{}' is not a pattern of C, M and I", pattern))?;
+
+        Ok(Attempt {
+            word: Cow::Owned(word),
+            score,
+        })
+    }
+}
+
 /// A struct that represents the possible type of scoring for a word
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Score {
@@ -110,8 +215,20 @@ pub enum Score {
     Incorrect,
 }
 
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Score::Correct => 'C',
+            Score::Misplaced => 'M',
+            Score::Incorrect => 'I',
+        };
+        write!(f, "{}", c)
+    }
+}
+
 impl Score {
     /// This function should return the color of the attempt
+    #[allow(clippy::collapsible_else_if, clippy::needless_return)]
     pub fn color(answer: &str, guess: &str) -> [Self; 5] {
         // Check if the length of the answer and guess is 5
         assert_eq!(answer.len(), 5);
@@ -147,6 +264,56 @@ impl Score {
         return final_score;
     }
 
+    /// Parses a pattern written with `C` for correct, `M` for misplaced and `I`
+    /// for incorrect, e.g. `CIIMI`
+    pub fn from_pattern(pattern: &str) -> Option<[Self; 5]> {
+        if pattern.len() != 5 {
+            return None;
+        }
+
+        let mut score = [Score::Correct; 5];
+        for (i, ch) in pattern.chars().enumerate() {
+            score[i] = match ch {
+                'C' => Score::Correct,
+                'M' => Score::Misplaced,
+                'I' => Score::Incorrect,
+                _ => return None,
+            };
+        }
+        Some(score)
+    }
+
+    /// Writes the pattern the way `Score::from_pattern` reads it
+    pub fn to_pattern(pattern: &[Self; 5]) -> String {
+        pattern.iter().map(|score| score.to_string()).collect()
+    }
+
+    /// The position of the pattern in `Score::permutations`, between 0 and 242
+    pub(crate) fn index(pattern: &[Self; 5]) -> usize {
+        pattern.iter().fold(0, |index, score| {
+            index * 3
+                + match score {
+                    Score::Correct => 0,
+                    Score::Misplaced => 1,
+                    Score::Incorrect => 2,
+                }
+        })
+    }
+
+    /// The pattern at the position in `Score::permutations`
+    pub(crate) fn from_index(mut index: usize) -> [Self; 5] {
+        let mut pattern = [Score::Correct; 5];
+        for score in pattern.iter_mut().rev() {
+            *score = match index % 3 {
+                0 => Score::Correct,
+                1 => Score::Misplaced,
+                _ => Score::Incorrect,
+            };
+            index /= 3;
+        }
+        pattern
+    }
+
     pub fn permutations() -> impl Iterator<Item = [Self; 5]> {
         itertools::iproduct!(
             [Score::Correct, Score::Misplaced, Score::Incorrect],
@@ -160,11 +327,122 @@ impl Score {
 }
 
 pub trait Solver {
+    /// The name the solver is known by, e.g. in a `SolverRegistry`
+    fn name(&self) -> &str {
+        "custom"
+    }
+
+    /// Forgets everything about the current game so the solver can play the
+    /// next one
+    fn reset(&mut self) {}
+
+    /// This function should return the `n` best guesses for the given history
+    /// along with their scores, the best guess first. Ties are broken by
+    /// `GuessEvaluation::cmp_rank`, so the same history always gets the same
+    /// ranking
+    fn rank(&mut self, history: &[Attempt], n: usize) -> Vec<GuessEvaluation>;
+
     /// This function should return the word that the solver thinks is the correct answer
-    fn solve(&mut self, history: &[Attempt]) -> String;
+    fn solve(&mut self, history: &[Attempt]) -> String {
+        self.try_solve(history)
+            .unwrap_or_else(|err| panic!("solver has no guess left: {}", err))
+    }
+
+    /// Like `solve`, but says why the solver has no guess instead of
+    /// panicking, e.g. no word fits the feedback or a program stopped answering
+    fn try_solve(&mut self, history: &[Attempt]) -> Result<String, String> {
+        self.rank(history, 1)
+            .into_iter()
+            .next()
+            .map(|evaluation| evaluation.word.into_owned())
+            .ok_or_else(|| "no word fits the feedback".to_string())
+    }
+
+    /// This function should split the candidates left after the history by
+    /// the feedback the guess would get, solvers that do not keep candidates
+    /// return `None`
+    fn buckets(&mut self, _history: &[Attempt], _guess: &str) -> Option<Vec<Bucket>> {
+        None
+    }
+
+    /// This function should explain why the solver picks its next guess, from
+    /// the two best guesses of `rank` and the `buckets` of the first. Solvers
+    /// that cannot explain themselves return `None`
+    fn explain(&mut self, history: &[Attempt]) -> Option<Explanation> {
+        let mut ranked = self.rank(history, 2).into_iter();
+        let guess = ranked.next()?;
+        let runner_up = ranked.next();
+        let buckets = self.buckets(history, &guess.word)?;
+
+        Some(Explanation::new(guess, buckets, runner_up))
+    }
+}
+
+impl<S: Solver + ?Sized> Solver for &mut S {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+
+    fn rank(&mut self, history: &[Attempt], n: usize) -> Vec<GuessEvaluation> {
+        (**self).rank(history, n)
+    }
+
+    fn solve(&mut self, history: &[Attempt]) -> String {
+        (**self).solve(history)
+    }
+
+    fn try_solve(&mut self, history: &[Attempt]) -> Result<String, String> {
+        (**self).try_solve(history)
+    }
+
+    fn buckets(&mut self, history: &[Attempt], guess: &str) -> Option<Vec<Bucket>> {
+        (**self).buckets(history, guess)
+    }
+
+    fn explain(&mut self, history: &[Attempt]) -> Option<Explanation> {
+        (**self).explain(history)
+    }
+}
+
+impl<S: Solver + ?Sized> Solver for Box<S> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+
+    fn rank(&mut self, history: &[Attempt], n: usize) -> Vec<GuessEvaluation> {
+        (**self).rank(history, n)
+    }
+
+    fn solve(&mut self, history: &[Attempt]) -> String {
+        (**self).solve(history)
+    }
+
+    fn try_solve(&mut self, history: &[Attempt]) -> Result<String, String> {
+        (**self).try_solve(history)
+    }
+
+    fn buckets(&mut self, history: &[Attempt], guess: &str) -> Option<Vec<Bucket>> {
+        (**self).buckets(history, guess)
+    }
+
+    fn explain(&mut self, history: &[Attempt]) -> Option<Explanation> {
+        (**self).explain(history)
+    }
 }
 
 impl Solver for fn(attempts: &[Attempt]) -> String {
+    fn rank(&mut self, history: &[Attempt], _n: usize) -> Vec<GuessEvaluation> {
+        vec![GuessEvaluation::unscored((*self)(history))]
+    }
+
     fn solve(&mut self, history: &[Attempt]) -> String {
         (*self)(history)
     }
@@ -181,6 +459,10 @@ mod tests {
             (|$history: ident| $impl:block ) => {{
                 struct S;
                 impl Solver for S {
+                    fn rank(&mut self, history: &[Attempt], _n: usize) -> Vec<GuessEvaluation> {
+                        vec![GuessEvaluation::unscored(self.solve(history))]
+                    }
+
                     fn solve(&mut self, $history: &[Attempt]) -> String {
                         $impl
                     }
@@ -217,6 +499,70 @@ mod tests {
             });
             assert_eq!(wordle.game(answer, solver), Ok(2));
         }
+
+        #[test]
+        fn test_simulate_stops_a_stuck_solver() {
+            let wordle = Wordle::new();
+            let solver = solver!(|_history| { "world".to_string() });
+            let result = wordle.simulate("hello", solver, 3);
+            assert_eq!(result.unwrap_err(), "no answer after 3 guesses");
+
+            let solver = solver!(|_history| { "xxxxx".to_string() });
+            assert!(wordle.simulate("hello", solver, 3).is_err());
+
+            struct Empty;
+            impl Solver for Empty {
+                fn rank(&mut self, _history: &[Attempt], _n: usize) -> Vec<GuessEvaluation> {
+                    vec![]
+                }
+            }
+            assert!(wordle.simulate("hello", Empty, 3).is_err());
+        }
+
+        fn guesses<S: Solver>(wordle: &Wordle, answer: &'static str, solver: S) -> Vec<String> {
+            let history = wordle
+                .simulate(answer, solver, Wordle::MAX_SIMULATED_GUESSES)
+                .unwrap();
+            history
+                .iter()
+                .map(|attempt| attempt.word.to_string())
+                .collect()
+        }
+
+        /// Locks in the games every solver plays, a change here changes how
+        /// the solvers rank guesses
+        #[test]
+        fn test_solvers_play_the_same_games_every_run() {
+            use crate::algorithms::{
+                HighestEntropyAlgorithm, NaiveAlgorithm, OptimizedEntropyAlgorithm,
+            };
+
+            let wordle = Wordle::new();
+            let golden: [(&str, &'static str, &[&str]); 9] = [
+                ("naive", "cigar", &["which", "basic", "cigar"]),
+                ("naive", "rebut", &["which", "about", "debut", "rebut"]),
+                ("naive", "humph", &["which", "harsh", "hough", "humph"]),
+                ("entropy", "cigar", &["tares", "grail", "cigar"]),
+                ("entropy", "rebut", &["tares", "retie", "recto", "rebut"]),
+                ("entropy", "humph", &["tares", "colin", "pygmy", "humph"]),
+                ("optimized", "cigar", &["tared", "grans", "cigar"]),
+                (
+                    "optimized",
+                    "rebut",
+                    &["tared", "wrote", "entry", "remit", "rebut"],
+                ),
+                ("optimized", "humph", &["tared", "loins", "bumpy", "humph"]),
+            ];
+
+            for (solver, answer, expected) in golden {
+                let played = match solver {
+                    "naive" => guesses(&wordle, answer, NaiveAlgorithm::new()),
+                    "entropy" => guesses(&wordle, answer, HighestEntropyAlgorithm::new()),
+                    _ => guesses(&wordle, answer, OptimizedEntropyAlgorithm::new()),
+                };
+                assert_eq!(played, expected, "{solver} playing {answer}");
+            }
+        }
     }
 
     mod score_calculation {
@@ -275,6 +621,35 @@ mod tests {
             assert_eq!(Score::color(answer, guess), result!(I C I I I));
         }
 
+        #[test]
+        fn test_score_pattern_round_trip() {
+            assert_eq!(Score::from_pattern("CMIIC"), Some(result!(C M I I C)));
+            assert_eq!(Score::to_pattern(&result!(C M I I C)), "CMIIC");
+            assert_eq!(Score::from_pattern("CMIIX"), None);
+            assert_eq!(Score::from_pattern("CMII"), None);
+        }
+
+        #[test]
+        fn test_attempt_round_trip() {
+            let attempt: Attempt = "Tares:immii".parse().unwrap();
+            assert_eq!(attempt.word, "tares");
+            assert_eq!(attempt.score, Score::color("cigar", "tares"));
+            assert_eq!(attempt.to_string(), "tares:IMMII");
+
+            assert!("tares".parse::<Attempt>().is_err());
+            assert!("tar:IMMII".parse::<Attempt>().is_err());
+            assert!("tares:IMXII".parse::<Attempt>().is_err());
+        }
+
+        #[test]
+        fn test_score_index_follows_permutations() {
+            for (index, pattern) in Score::permutations().enumerate() {
+                assert_eq!(Score::index(&pattern), index);
+                assert_eq!(Score::from_index(index), pattern);
+            }
+            assert_eq!(Score::index(&result!(I I I I I)), 242);
+        }
+
         #[test]
         fn test_score_color_with_some_correct_and_misplaced_words_edge_case_1() {
             let answer = "admin";
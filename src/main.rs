@@ -1,7 +1,31 @@
-use std::collections::HashSet;
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    io::BufReader,
+    ops::Range,
+    path::{Path, PathBuf},
+    process::{self, Stdio},
+    time::SystemTime,
+};
 
-use clap::{arg, Parser, Subcommand, ValueEnum};
-use wordl::Wordle;
+use clap::{Parser, Subcommand};
+#[cfg(feature = "serde")]
+use wordl::record::GameRecord;
+#[cfg(feature = "stats")]
+use wordl::stats::{Mode, Stats};
+#[cfg(feature = "tui")]
+use wordl::tui;
+use wordl::{
+    algorithms::{Prior, SecondGuessCache, SecondGuessTable, SolverRegistry, SolverSpec},
+    display::{self, Tiles},
+    fibble::Fibble,
+    openers,
+    referee::{AnswerPolicy, Referee, Rules, Summary},
+    schedule::{Date, Schedule},
+    search::Query,
+    stats::Outcome,
+    Attempt, Score, Solver, Wordle,
+};
 
 #[derive(Parser)]
 #[command(version, long_about = None)]
@@ -10,60 +34,731 @@ struct Args {
     commands: Command,
 }
 
-#[derive(Debug, Clone, ValueEnum)]
-enum Algorithm {
-    Random,
-    HighestEntropy,
-    OptimizedEntropy,
+/// Builds the solver of the spec, the prior and opener given on the command
+/// line are used unless the spec sets them
+fn solver(
+    registry: &SolverRegistry,
+    spec: SolverSpec,
+    prior: Option<Prior>,
+    opener: Option<String>,
+) -> (SolverSpec, Box<dyn Solver>) {
+    let spec = match prior {
+        Some(prior) => spec.with_default("prior", prior.to_string()),
+        None => spec,
+    };
+    let spec = match opener {
+        Some(opener) => spec.with_default("opener", opener),
+        None => spec,
+    };
+    match registry.build(&spec) {
+        Ok(solver) => (spec, solver),
+        Err(err) => exit_with(&err),
+    }
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(2);
+}
+
+/// The opener given on the command line, or the best one from the cache file
+fn opener(opener: Option<String>, opener_cache: Option<PathBuf>) -> Option<String> {
+    opener.or_else(|| {
+        opener_cache.map(|path| {
+            openers::load_best(&path).unwrap_or_else(|err| {
+                exit_with(&format!(
+                    "Cannot read the opener cache {}: {}",
+                    path.display(),
+                    err
+                ))
+            })
+        })
+    })
+}
+
+/// The lies of the Fibble games of a run, from `--lies` or the spec of a
+/// fibble solver, and the spec with them. Only the fibble solver is told the
+/// lies, the others play the games as they would any other.
+fn fibble_lies(
+    registry: &SolverRegistry,
+    spec: SolverSpec,
+    lies: Option<usize>,
+) -> Result<(SolverSpec, Option<usize>), String> {
+    let takes_lies = registry.resolve(&spec.name) == Some("fibble");
+    let lies = match (lies, spec.get("lies")) {
+        (Some(lies), Some(value)) if value.parse() != Ok(lies) => {
+            return Err(format!(
+                "--lies {} does not match lies={} of the solver",
+                lies, value
+            ))
+        }
+        (None, Some(value)) => Some(
+            value
+                .parse()
+                .map_err(|_| format!("'{}' is not a number of lies", value))?,
+        ),
+        // the fibble solver expects its default lies from the host too
+        (None, None) if takes_lies => Some(Fibble::DEFAULT_LIES),
+        (lies, _) => lies,
+    };
+    let spec = match lies {
+        Some(lies) if takes_lies => spec.with_default("lies", lies.to_string()),
+        _ => spec,
+    };
+    Ok((spec, lies))
+}
+
+/// Plays a game for every answer and returns the total number of guesses
+/// along with the number of games played, every game is handed to `on_game`
+/// once played with the time it started. A game the solver fails or does not
+/// solve in `max_guesses` is reported and left out. With a cache directory
+/// the second guesses after the opener of the solver are looked up in a
+/// `SecondGuessTable` instead of being worked out every game. With `lies` the
+/// games are Fibble games, seeded by their answer.
+fn simulate_run<S: Solver>(
+    answers: &[&'static str],
+    cache_dir: Option<PathBuf>,
+    key: &str,
+    lies: Option<usize>,
+    max_guesses: usize,
+    mut solver: S,
+    mut on_game: impl FnMut(&'static str, SystemTime, Vec<Attempt<'static>>),
+) -> (usize, usize) {
+    let table = cache_dir.map(|dir| {
+        let opener = solver.solve(&[]);
+        let lexicon = wordl::algorithms::Utils::word_frequencies();
+        let table = SecondGuessTable::load_or_build(&dir, &opener, &lexicon, key, &mut solver);
+        table.unwrap_or_else(|err| {
+            exit_with(&format!(
+                "Cannot use the cache directory {}: {}",
+                dir.display(),
+                err
+            ))
+        })
+    });
+
+    let wordle = Wordle::new();
+    let (mut counter, mut played) = (0, 0);
+    for &ans in answers {
+        solver.reset();
+        let started = SystemTime::now();
+        let game_history = match (lies, &table) {
+            (Some(lies), _) => Fibble::for_answer(lies, ans)
+                .unwrap_or_else(|err| exit_with(&err))
+                .simulate(ans, &mut solver, max_guesses),
+            (None, Some(table)) => {
+                wordle.simulate(ans, SecondGuessCache::new(&mut solver, table), max_guesses)
+            }
+            (None, None) => wordle.simulate(ans, &mut solver, max_guesses),
+        };
+        match game_history {
+            Ok(game_history) => {
+                counter += game_history.len();
+                played += 1;
+                on_game(ans, started, game_history);
+            }
+            Err(err) => eprintln!("The game for '{}' stopped: {}", ans, err),
+        }
+    }
+    (counter, played)
 }
 
 #[derive(Debug, Subcommand)]
 enum Command {
     #[command(long_about = "Run the previous wordle games for benchmarking the algorithms")]
     SimulateRun {
-        // Algorithm to use
+        // Solver to use, by name with options, e.g. `entropy:opener=crane,prior=sigmoid`
+        // (naive, entropy, optimized, fibble, external)
         #[arg(short, long)]
-        algorithm: Algorithm,
+        algorithm: SolverSpec,
+
+        // Play Fibble games where every feedback row has that many wrong tiles,
+        // 1 with the fibble solver unless `-a fibble:lies=2` says otherwise
+        #[arg(long)]
+        lies: Option<usize>,
+
+        // Program the external solver runs, see `wordl::algorithms::ExternalSolver` for the protocol
+        #[arg(long)]
+        command: Option<String>,
+
+        // Guesses every game gets, 9 for Fibble games and 20 otherwise
+        #[arg(long)]
+        max_guesses: Option<usize>,
 
         // Number of words to guess
         #[arg(short, long)]
         count: Option<usize>,
+
+        // Play the daily puzzles with these numbers instead, e.g. 200..400
+        #[arg(long, value_parser = parse_range, requires = "schedule", conflicts_with = "count")]
+        range: Option<Range<usize>>,
+
+        // File with the answers of the daily puzzles in order, one per line
+        #[arg(long, requires = "range")]
+        schedule: Option<PathBuf>,
+
+        // Prior turning n-gram counts into answer probabilities (uniform, raw, sigmoid, answers)
+        #[arg(short, long)]
+        prior: Option<Prior>,
+
+        // First guess of every game instead of the solver's own
+        #[arg(short, long)]
+        opener: Option<String>,
+
+        // Open with the best word of a cache file written by `openers --save`
+        #[arg(long, conflicts_with = "opener")]
+        opener_cache: Option<PathBuf>,
+
+        // Keep the best second guess after every feedback to the opener in the
+        // directory, so later runs do not work them out again
+        #[arg(long)]
+        cache_dir: Option<PathBuf>,
+
+        // Print the guesses of every game with their colors
+        #[arg(short, long)]
+        verbose: bool,
+
+        // Append every game to a record file, see `wordl replay`
+        #[cfg(feature = "serde")]
+        #[arg(long)]
+        records: Option<PathBuf>,
+    },
+    #[command(long_about = "Suggest guesses for a game played somewhere else")]
+    Assist {
+        // Solver to use, by name with options, e.g. `entropy:opener=crane,prior=sigmoid`
+        // (naive, entropy, optimized)
+        #[arg(short, long)]
+        algorithm: SolverSpec,
+
+        // Prior turning n-gram counts into answer probabilities (uniform, raw, sigmoid, answers)
+        #[arg(short, long)]
+        prior: Option<Prior>,
+
+        // Show why each guess was suggested
+        #[arg(short, long)]
+        explain: bool,
+
+        // First guess to suggest instead of the solver's own
+        #[arg(short, long)]
+        opener: Option<String>,
+
+        // Suggest the best word of a cache file written by `openers --save` first
+        #[arg(long, conflicts_with = "opener")]
+        opener_cache: Option<PathBuf>,
+
+        // Full screen terminal UI, the colors are set on the tiles instead of typed
+        #[cfg(feature = "tui")]
+        #[arg(long)]
+        tui: bool,
+    },
+    #[command(long_about = "Play a game against a random answer")]
+    Play {
+        // Seed picking the answer
+        #[arg(long)]
+        seed: Option<u64>,
+
+        // Play the daily puzzle of that day, e.g. 2022-01-15
+        #[arg(long, requires = "schedule", conflicts_with = "seed")]
+        date: Option<Date>,
+
+        // File with the answers of the daily puzzles in order, one per line
+        #[arg(long, requires = "date")]
+        schedule: Option<PathBuf>,
+
+        // Guesses have to keep the greens in place and use every letter found
+        #[arg(long)]
+        hard: bool,
+
+        // Play Fibble, every feedback row has that many tiles with a wrong color
+        #[arg(long, conflicts_with = "hard")]
+        lies: Option<usize>,
+
+        // Number of guesses, 6 or 9 with lies
+        #[arg(long)]
+        max_guesses: Option<usize>,
+
+        // Full screen terminal UI, with the suggestions of the solver one key away
+        #[cfg(feature = "tui")]
+        #[arg(long, conflicts_with = "lies")]
+        tui: bool,
+
+        // Solver giving the suggestions of the terminal UI, by name with options
+        #[cfg(feature = "tui")]
+        #[arg(short, long, default_value = "entropy")]
+        algorithm: SolverSpec,
+
+        // File keeping the statistics instead of the one in the data directory
+        #[cfg(feature = "stats")]
+        #[arg(long)]
+        stats_file: Option<PathBuf>,
+
+        // Leave the game out of the statistics
+        #[cfg(feature = "stats")]
+        #[arg(long, conflicts_with = "stats_file")]
+        no_stats: bool,
+
+        // Append the game to a record file once it is over, see `wordl replay`
+        #[cfg(feature = "serde")]
+        #[arg(long)]
+        save: Option<PathBuf>,
+    },
+    #[cfg(feature = "serde")]
+    #[command(
+        long_about = "Step through a game of a record file written by `play --save` or \
+        `simulate-run --records`, with the candidates left after every guess"
+    )]
+    Replay {
+        // The record file
+        file: PathBuf,
+
+        // Number of the game in the file, from 1
+        #[arg(short, long, default_value_t = 1)]
+        game: usize,
+
+        // Replay the first game with this answer instead
+        #[arg(short, long, conflicts_with = "game")]
+        answer: Option<String>,
+
+        // Number of candidates to show after every guess
+        #[arg(short, long, default_value_t = 10)]
+        limit: usize,
+
+        // Wait for enter before every guess
+        #[arg(short, long)]
+        step: bool,
+    },
+    #[command(
+        long_about = "Show the number and day of a daily puzzle, given either one, and its \
+        answer when a schedule file is given. Today's puzzle by default."
+    )]
+    Puzzle {
+        // A day like 2022-01-15 or a puzzle number
+        day: Option<String>,
+
+        // File with the answers of the daily puzzles in order, one per line
+        #[arg(long)]
+        schedule: Option<PathBuf>,
+    },
+    #[cfg(feature = "stats")]
+    #[command(long_about = "Show the statistics of the games played with `play`, by mode")]
+    Stats {
+        // Only show this mode, e.g. classic-5x6-hard
+        #[arg(short, long)]
+        mode: Option<Mode>,
+
+        // File keeping the statistics instead of the one in the data directory
+        #[arg(long)]
+        stats_file: Option<PathBuf>,
+
+        // Write every mode to a file, as CSV when it ends in .csv and as JSON otherwise
+        #[arg(long)]
+        export: Option<PathBuf>,
+    },
+    #[command(
+        long_about = "Score every allowed guess as a first move and rank them. The best \
+        openers can also be played through whole games to get the average game length."
+    )]
+    Openers {
+        // Prior turning n-gram counts into answer probabilities, words without weight are left out
+        #[arg(short, long, default_value = "answers")]
+        prior: Prior,
+
+        // Number of openers to show
+        #[arg(short, long, default_value_t = 20)]
+        top: usize,
+
+        // Number of the best openers to play whole games with
+        #[arg(short, long, default_value_t = 0)]
+        simulate: usize,
+
+        // Solver playing the simulated games, by name with options
+        #[arg(short, long, default_value = "entropy")]
+        algorithm: SolverSpec,
+
+        // Number of answers to simulate for every opener
+        #[arg(short, long)]
+        count: Option<usize>,
+
+        // Write the ranking to a cache file solvers can load with `--opener-cache`
+        #[arg(long)]
+        save: Option<PathBuf>,
+    },
+    #[command(
+        long_about = "Host a game for a player speaking the protocol of `wordl::referee` on \
+        stdin and stdout, or for the program given with --player. The summary of the game \
+        goes to stderr."
+    )]
+    Referee {
+        // Answer of the game, a random one is picked when none is given
+        #[arg(long, conflicts_with_all = ["seed", "adversarial"])]
+        answer: Option<String>,
+
+        // Seed picking the random answer
+        #[arg(long, conflicts_with = "adversarial")]
+        seed: Option<u64>,
+
+        // Pick no answer and give every guess the feedback that keeps the most answers
+        #[arg(long)]
+        adversarial: bool,
+
+        // Guesses have to keep the greens in place and use every letter found
+        #[arg(long)]
+        hard: bool,
+
+        // Number of guesses the player gets
+        #[arg(long, default_value_t = 6)]
+        max_guesses: usize,
+
+        // Program to play against instead of stdin and stdout
+        #[arg(long)]
+        player: Option<String>,
+
+        // File to write the summary of the game to
+        #[arg(long)]
+        summary: Option<PathBuf>,
+    },
+    #[cfg(feature = "server")]
+    #[command(
+        long_about = "Serve a JSON API on localhost to play games, get suggestions and list \
+        candidates, see `wordl::server` for the endpoints"
+    )]
+    Serve {
+        // Port to listen on
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+    },
+    #[command(
+        long_about = "Find the words that fit a pattern, e.g. `_R_NE +a -t` for the words \
+        matching _R_NE with an A somewhere and no T. Terms: `_r_ne` letters by position \
+        (_, ? or . when unknown), `+ae` or `+e2` required letters, `-tso` banned letters, \
+        `!a13` letter not at positions 1 and 3, whether or not it is in the word (add `+a` \
+        for a misplaced letter). Options go before the query."
+    )]
+    Search {
+        // Maximum number of words to show
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+
+        // The query terms
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        query: Vec<String>,
     },
 }
 
-fn main() {
-    // let wordle = Wordle::new();
-    // wordle.assist(wordl::algorithms::OptimizedEntropyAlgorithm::new());
+/// Hosts a game for the player program, or for whoever is on stdin and
+/// stdout without one
+fn host(referee: &Referee, policy: &AnswerPolicy, player: Option<String>) -> Summary {
+    let Some(player) = player else {
+        let stdin = std::io::stdin();
+        return referee
+            .play(policy, stdin.lock(), std::io::stdout())
+            .unwrap_or_else(|err| exit_with(&format!("Cannot talk to the player: {}", err)));
+    };
+
+    let mut parts = player.split_whitespace();
+    let program = parts
+        .next()
+        .unwrap_or_else(|| exit_with("The player command is empty"));
+    let mut child = process::Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap_or_else(|err| exit_with(&format!("Cannot start '{}': {}", player, err)));
+
+    let input = BufReader::new(child.stdout.take().expect("stdout is piped"));
+    let output = child.stdin.take().expect("stdin is piped");
+    let summary = referee
+        .play(policy, input, output)
+        .unwrap_or_else(|err| exit_with(&format!("Cannot talk to '{}': {}", player, err)));
+    // the player is done once its stdin is closed
+    let _ = child.wait();
+    summary
+}
+
+/// Plays a game on stdin and stdout and returns its attempts, there is no
+/// outcome when the input ends before the game does. The feedback comes from
+/// the Fibble host when there is one.
+fn play(
+    referee: &Referee,
+    answer: &'static str,
+    mut fibble: Option<Fibble>,
+) -> (Vec<Attempt<'static>>, Option<Outcome>) {
+    let mut attempts = Vec::new();
+    let color = display::color_enabled();
+    println!(
+        "Guess the word in {} tries (C for correct, M for misplaced, I for incorrect)",
+        referee.rules.max_guesses
+    );
+    if let Some(fibble) = &fibble {
+        println!("Tiles with a wrong color in every row: {}", fibble.lies());
+    }
+
+    let stdin = std::io::stdin();
+    let mut line = String::new();
+    while attempts.len() < referee.rules.max_guesses {
+        line.clear();
+        if stdin.read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        let guess = line.trim().to_ascii_lowercase();
+        if let Some(reason) = referee.check(&attempts, &guess) {
+            println!("'{}' is not allowed: {}", guess, reason);
+            continue;
+        }
+
+        let score = match &mut fibble {
+            Some(fibble) => fibble.feedback(answer, &guess),
+            None => Score::color(answer, &guess),
+        };
+        let solved = guess == answer;
+        attempts.push(Attempt {
+            word: Cow::Owned(guess),
+            score,
+        });
+        println!("  {}", Tiles::new(&attempts[attempts.len() - 1], color));
+        if solved {
+            println!("Solved in {} guesses", attempts.len());
+            let outcome = Outcome::Won(attempts.len());
+            return (attempts, Some(outcome));
+        }
+    }
+    if attempts.len() < referee.rules.max_guesses {
+        return (attempts, None);
+    }
+    println!("The answer was '{}'", answer);
+    (attempts, Some(Outcome::Lost))
+}
+
+/// Shows the guesses of a game one by one with the candidates they leave
+#[cfg(feature = "serde")]
+fn replay(record: &GameRecord, limit: usize, step: bool) {
+    let color = display::color_enabled();
+    let lexicon = wordl::algorithms::Utils::word_frequencies();
+    if record.lexicon != wordl::record::lexicon_id() {
+        println!(
+            "The game was played with the lexicon {}, the candidates may differ",
+            record.lexicon
+        );
+    }
+    let mode = if record.rules.hard_mode {
+        "hard"
+    } else {
+        "normal"
+    };
+    let variant = match record.lies {
+        Some(lies) => format!(", Fibble with {} lies per row", lies),
+        None => String::new(),
+    };
+    println!(
+        "Answer '{}', {} mode{}, played by {}",
+        record.answer,
+        mode,
+        variant,
+        record.solver.as_deref().unwrap_or("a person")
+    );
+
+    let mut line = String::new();
+    for i in 0..record.attempts.len() {
+        if step {
+            line.clear();
+            if std::io::stdin().read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+        }
+        let words = record.candidates(i + 1, &lexicon);
+        let shown: Vec<&str> = words.iter().take(limit).map(|(word, _)| *word).collect();
+        let more = if words.len() > limit { " ..." } else { "" };
+        println!(
+            "{} {}  {:>5} left  {}{}",
+            i + 1,
+            Tiles::new(&record.attempts[i], color),
+            words.len(),
+            shown.join(" "),
+            more
+        );
+    }
 
+    let result = if record.won() { "Won" } else { "Lost" };
+    let seconds = record.finished_at.saturating_sub(record.started_at);
+    println!(
+        "{} in {} guesses, {}s",
+        result,
+        record.attempts.len(),
+        seconds
+    );
+}
+
+/// Parses a range of puzzle numbers like `200..400`
+fn parse_range(s: &str) -> Result<Range<usize>, String> {
+    let invalid = || format!("'{}' is not a range like 200..400", s);
+    let (start, end) = s.split_once("..").ok_or_else(invalid)?;
+    let (start, end) = (start.trim(), end.trim());
+    let start = if start.is_empty() {
+        0
+    } else {
+        start.parse().map_err(|_| invalid())?
+    };
+    let end = if end.is_empty() {
+        usize::MAX
+    } else {
+        end.parse().map_err(|_| invalid())?
+    };
+    Ok(start..end)
+}
+
+fn load_schedule(path: &Path) -> Schedule {
+    Schedule::load(path).unwrap_or_else(|err| {
+        exit_with(&format!(
+            "Cannot read the schedule {}: {}",
+            path.display(),
+            err
+        ))
+    })
+}
+
+/// The statistics file given on the command line or the default one
+#[cfg(feature = "stats")]
+fn stats_path(stats_file: Option<PathBuf>) -> PathBuf {
+    stats_file.or_else(Stats::default_path).unwrap_or_else(|| {
+        exit_with("No data directory to keep the statistics in, use --stats-file")
+    })
+}
+
+/// Adds the game to the statistics and shows the record of its mode
+#[cfg(feature = "stats")]
+fn record(path: &Path, mode: Mode, outcome: Outcome) {
+    let mut stats = Stats::load(path).unwrap_or_else(|err| {
+        exit_with(&format!(
+            "Cannot read the statistics {}: {}",
+            path.display(),
+            err
+        ))
+    });
+    println!();
+    println!("Statistics ({})", mode);
+    match stats.add(mode, outcome) {
+        Ok(record) => print!("{}", record),
+        Err(err) => exit_with(&format!("Cannot count the game: {}", err)),
+    }
+    if let Err(err) = stats.save(path) {
+        exit_with(&format!(
+            "Cannot write the statistics {}: {}",
+            path.display(),
+            err
+        ));
+    }
+}
+
+fn main() {
     let args: Args = Args::parse();
     match args.commands {
-        Command::SimulateRun { algorithm, count } => {
-            let wordle = Wordle::new();
-            // not load the whole thing just load the required amount
-            let wordle_answers: HashSet<&'static str> = include_str!("../dataset/wordle/ans.txt")
-                .lines()
-                .map(|word| word.trim())
-                .take(count.unwrap_or(usize::MAX))
-                .collect();
-
-            let mut counter = 0;
+        Command::SimulateRun {
+            algorithm,
+            lies,
+            command,
+            max_guesses,
+            count,
+            range,
+            schedule,
+            prior,
+            opener: first_guess,
+            opener_cache,
+            cache_dir,
+            verbose,
+            #[cfg(feature = "serde")]
+            records,
+        } => {
+            let registry = SolverRegistry::default();
+            let first_guess = opener(first_guess, opener_cache);
+            let algorithm = match command {
+                Some(command) => algorithm.with_default("command", command),
+                None => algorithm,
+            };
+            let (algorithm, lies) =
+                fibble_lies(&registry, algorithm, lies).unwrap_or_else(|err| exit_with(&err));
+            let (spec, solver) = solver(&registry, algorithm, prior, first_guess);
+            // an external program can change between runs and the lies of a
+            // Fibble game change the feedback, their guesses are not kept
+            let cache_dir = cache_dir.filter(|_| lies.is_none() && solver.name() != "external");
+            // the opener is part of the table file name already
+            let key = spec
+                .options
+                .iter()
+                .filter(|(option, _)| option.as_str() != "opener")
+                .fold(solver.name().to_string(), |key, (option, value)| {
+                    format!("{}-{}={}", key, option, value)
+                });
+            let wordle_answers: Vec<&'static str> = match (range, schedule) {
+                (Some(range), Some(path)) => {
+                    let answers = load_schedule(&path).answers(range.clone()).to_vec();
+                    if answers.is_empty() {
+                        exit_with(&format!("The schedule has no puzzle in {:?}", range));
+                    }
+                    answers
+                }
+                // not load the whole thing just load the required amount
+                _ => include_str!("../dataset/wordle/ans.txt")
+                    .lines()
+                    .map(|word| word.trim())
+                    .take(count.unwrap_or(usize::MAX))
+                    .collect(),
+            };
 
-            for ans in &wordle_answers {
-                let result = match algorithm {
-                    Algorithm::OptimizedEntropy => {
-                        wordle.game(ans, wordl::algorithms::OptimizedEntropyAlgorithm::new())
+            #[cfg(feature = "serde")]
+            let mut games = Vec::new();
+            #[cfg_attr(not(feature = "serde"), allow(unused_variables))]
+            let max_guesses = max_guesses.unwrap_or(match lies {
+                Some(_) => Fibble::MAX_GUESSES,
+                None => Wordle::MAX_SIMULATED_GUESSES,
+            });
+            #[cfg(feature = "serde")]
+            let rules = Rules {
+                max_guesses,
+                ..Rules::default()
+            };
+            #[cfg_attr(not(feature = "serde"), allow(unused_variables))]
+            let on_game =
+                |answer: &'static str, started: SystemTime, game_history: Vec<Attempt<'static>>| {
+                    if verbose {
+                        Wordle::print_game(&game_history);
                     }
-                    Algorithm::Random => wordle.game(ans, wordl::algorithms::NaiveAlgorithm::new()),
-                    Algorithm::HighestEntropy => {
-                        wordle.game(ans, wordl::algorithms::HighestEntropyAlgorithm::new())
+                    #[cfg(feature = "serde")]
+                    if records.is_some() {
+                        let record = GameRecord {
+                            lies,
+                            started_at: wordl::record::timestamp(started),
+                            ..GameRecord::new(answer, rules.clone(), Some(spec.to_string()))
+                        };
+                        games.push(record.finish(game_history));
                     }
                 };
-                counter += result.unwrap();
+            let (counter, played) = simulate_run(
+                &wordle_answers,
+                cache_dir,
+                &key,
+                lies,
+                max_guesses,
+                solver,
+                on_game,
+            );
+            #[cfg(feature = "serde")]
+            if let Some(path) = records {
+                if let Err(err) = GameRecord::append(&path, &games) {
+                    exit_with(&format!("Cannot write {}: {}", path.display(), err));
+                }
+                println!("Saved {} games to {}", games.len(), path.display());
             }
 
             println!("Total number of guesses attempted: {:?}", counter);
 
-            let no_of_moves = counter as f64 / wordle_answers.len() as f64;
+            if played == 0 {
+                exit_with("No game was played");
+            }
+            if played < wordle_answers.len() {
+                println!("Games played: {} of {}", played, wordle_answers.len());
+            }
+            let no_of_moves = counter as f64 / played as f64;
             println!("Average number of moves: {:?}", no_of_moves);
 
             // assuming an average human can solve it in 4 tries
@@ -71,6 +766,346 @@ fn main() {
 
             println!("Efficiency over a human: {:?} %", efficiency * 100f64);
         }
+        Command::Assist {
+            algorithm,
+            prior,
+            explain,
+            opener: first_guess,
+            opener_cache,
+            #[cfg(feature = "tui")]
+            tui,
+        } => {
+            let wordle = Wordle::new();
+            let registry = SolverRegistry::default();
+            let first_guess = opener(first_guess, opener_cache);
+            let (_, solver) = solver(&registry, algorithm, prior, first_guess);
+            #[cfg(feature = "tui")]
+            if tui {
+                let mut app = tui::App::new(tui::Mode::Assist, solver, Referee::default());
+                if let Err(err) = tui::run(&mut app) {
+                    exit_with(&format!("Cannot use the terminal: {}", err));
+                }
+                return;
+            }
+            wordle.assist(solver, explain);
+        }
+        Command::Play {
+            seed,
+            date,
+            schedule,
+            hard,
+            lies,
+            max_guesses,
+            #[cfg(feature = "tui")]
+            tui,
+            #[cfg(feature = "tui")]
+            algorithm,
+            #[cfg(feature = "stats")]
+            stats_file,
+            #[cfg(feature = "stats")]
+            no_stats,
+            #[cfg(feature = "serde")]
+            save,
+        } => {
+            #[cfg(feature = "stats")]
+            let path = (!no_stats).then(|| stats_path(stats_file));
+            let rules = Rules {
+                max_guesses: max_guesses.unwrap_or(match lies {
+                    Some(_) => Fibble::MAX_GUESSES,
+                    None => Rules::default().max_guesses,
+                }),
+                hard_mode: hard,
+                ..Rules::default()
+            };
+            let referee = Referee::new(rules.clone());
+            let seed = seed.unwrap_or_else(rand::random);
+            let answer = match (date, schedule) {
+                (Some(date), Some(path)) => {
+                    let number = Schedule::number(date).unwrap_or_else(|| {
+                        exit_with(&format!("The first puzzle is from {}", Schedule::FIRST_DAY))
+                    });
+                    let answer = load_schedule(&path).answer(number).unwrap_or_else(|| {
+                        exit_with(&format!("The schedule stops before puzzle {}", number))
+                    });
+                    println!("Puzzle {} of {}", number, date);
+                    answer
+                }
+                _ => referee.random_answer(seed),
+            };
+            let fibble = lies.map(|lies| {
+                let fibble = match date {
+                    // everyone playing the puzzle of the day gets the same lies
+                    Some(_) => Fibble::for_answer(lies, answer),
+                    None => Fibble::new(lies, seed),
+                };
+                fibble.unwrap_or_else(|err| exit_with(&err))
+            });
+            #[cfg(feature = "serde")]
+            let game = GameRecord {
+                lies,
+                ..GameRecord::new(answer, rules.clone(), None)
+            };
+            #[cfg(feature = "tui")]
+            #[cfg_attr(not(feature = "serde"), allow(unused_variables))]
+            let (attempts, outcome) = if tui {
+                let (_, solver) = solver(&SolverRegistry::default(), algorithm, None, None);
+                let mut app = tui::App::new(tui::Mode::Play { answer }, solver, referee);
+                if let Err(err) = tui::run(&mut app) {
+                    exit_with(&format!("Cannot use the terminal: {}", err));
+                }
+                // a game left before its end is not counted
+                let outcome = app.is_over().then(|| match app.attempts().last() {
+                    Some(attempt) if attempt.score == [Score::Correct; 5] => {
+                        Outcome::Won(app.attempts().len())
+                    }
+                    _ => Outcome::Lost,
+                });
+                (app.attempts().to_vec(), outcome)
+            } else {
+                play(&referee, answer, fibble)
+            };
+            #[cfg(not(feature = "tui"))]
+            #[cfg_attr(not(feature = "serde"), allow(unused_variables))]
+            let (attempts, outcome) = play(&referee, answer, fibble);
+
+            #[cfg(feature = "serde")]
+            if let (Some(save), Some(_)) = (&save, outcome) {
+                if let Err(err) = GameRecord::append(save, &[game.finish(attempts)]) {
+                    exit_with(&format!("Cannot write {}: {}", save.display(), err));
+                }
+            }
+            #[cfg(feature = "stats")]
+            if let (Some(path), Some(outcome)) = (path, outcome) {
+                let mode = match lies {
+                    Some(lies) => Mode::fibble(lies, rules.max_guesses),
+                    None => Mode::classic(hard, rules.max_guesses),
+                };
+                record(&path, mode, outcome);
+            }
+        }
+        #[cfg(feature = "serde")]
+        Command::Replay {
+            file,
+            game,
+            answer,
+            limit,
+            step,
+        } => {
+            let records = GameRecord::load(&file).unwrap_or_else(|err| {
+                exit_with(&format!("Cannot read {}: {}", file.display(), err))
+            });
+            let record = match answer {
+                Some(answer) => records
+                    .iter()
+                    .find(|record| record.answer.eq_ignore_ascii_case(&answer))
+                    .unwrap_or_else(|| exit_with(&format!("No game with the answer '{}'", answer))),
+                None => records.get(game.wrapping_sub(1)).unwrap_or_else(|| {
+                    exit_with(&format!("There are {} games in the file", records.len()))
+                }),
+            };
+            replay(record, limit, step);
+        }
+        Command::Puzzle { day, schedule } => {
+            let (number, date) = match day {
+                Some(day) => match day.parse::<usize>() {
+                    Ok(number) => {
+                        let date = Schedule::date(number).unwrap_or_else(|| {
+                            exit_with(&format!("Puzzle {} is too far in the future", number))
+                        });
+                        (number, date)
+                    }
+                    Err(_) => {
+                        let date: Date = day.parse().unwrap_or_else(|err: String| exit_with(&err));
+                        let number = Schedule::number(date).unwrap_or_else(|| {
+                            exit_with(&format!("The first puzzle is from {}", Schedule::FIRST_DAY))
+                        });
+                        (number, date)
+                    }
+                },
+                None => {
+                    let date = Date::today();
+                    (Schedule::number(date).unwrap_or(0), date)
+                }
+            };
+
+            print!("Puzzle {} of {}", number, date);
+            match schedule.map(|path| load_schedule(&path).answer(number)) {
+                Some(Some(answer)) => println!(": {}", answer),
+                Some(None) => println!(", past the end of the schedule"),
+                None => println!(),
+            }
+        }
+        #[cfg(feature = "stats")]
+        Command::Stats {
+            mode,
+            stats_file,
+            export,
+        } => {
+            let path = stats_path(stats_file);
+            let stats = Stats::load(&path).unwrap_or_else(|err| {
+                exit_with(&format!(
+                    "Cannot read the statistics {}: {}",
+                    path.display(),
+                    err
+                ))
+            });
+
+            if let Some(export) = export {
+                let contents = match export.extension() {
+                    Some(extension) if extension == "csv" => stats.to_csv(),
+                    _ => stats.to_json(),
+                };
+                if let Err(err) = std::fs::write(&export, contents) {
+                    exit_with(&format!("Cannot write {}: {}", export.display(), err));
+                }
+                println!("Exported the statistics to {}", export.display());
+                return;
+            }
+
+            let records = stats
+                .records
+                .iter()
+                .filter(|(record_mode, _)| mode.as_ref().is_none_or(|mode| mode == *record_mode));
+            let mut shown = 0;
+            for (mode, record) in records {
+                if shown > 0 {
+                    println!();
+                }
+                println!("Statistics ({})", mode);
+                print!("{}", record);
+                shown += 1;
+            }
+            if shown == 0 {
+                println!("No games played yet");
+            }
+        }
+        Command::Openers {
+            prior,
+            top,
+            simulate,
+            algorithm,
+            count,
+            save,
+        } => {
+            let lexicon = wordl::algorithms::Utils::word_frequencies();
+            let weights = prior.weights(&lexicon);
+            let candidates: BTreeMap<_, _> = lexicon
+                .iter()
+                .filter(|(word, _)| weights[*word] > 0.0)
+                .map(|(&word, &count)| (word, count))
+                .collect();
+
+            println!(
+                "Scoring {} openers against {} candidates",
+                lexicon.len(),
+                candidates.len()
+            );
+            let mut ranked = openers::rank(lexicon.keys(), &candidates, &weights);
+
+            let wordle = Wordle::new();
+            let answers: Vec<&'static str> = include_str!("../dataset/wordle/ans.txt")
+                .lines()
+                .map(|word| word.trim())
+                .take(count.unwrap_or(usize::MAX))
+                .collect();
+
+            let registry = SolverRegistry::default();
+            for opener in ranked.iter_mut().take(simulate) {
+                let mut spec = algorithm.clone();
+                spec.options
+                    .insert("opener".to_string(), opener.evaluation.word.to_string());
+                let (_, solver) = solver(&registry, spec, None, None);
+                let average_moves = openers::average_moves(&wordle, &answers, solver)
+                    .unwrap_or_else(|err| exit_with(&err));
+                opener.average_moves = Some(average_moves);
+            }
+
+            println!(
+                "{:>4}  {:<5}  {:>7}  {:>14}  {:>10}  {:>13}",
+                "rank", "word", "entropy", "expected left", "worst case", "average moves"
+            );
+            for (rank, opener) in ranked.iter().take(top).enumerate() {
+                let evaluation = &opener.evaluation;
+                let average_moves = opener
+                    .average_moves
+                    .map_or_else(|| "-".to_string(), |moves| format!("{:.3}", moves));
+                println!(
+                    "{:>4}  {:<5}  {:>7.3}  {:>14.2}  {:>10}  {:>13}",
+                    rank + 1,
+                    evaluation.word,
+                    evaluation.entropy,
+                    evaluation.expected_remaining,
+                    evaluation.worst_case,
+                    average_moves
+                );
+            }
+
+            if let Some(path) = save {
+                if let Err(err) = openers::save(&path, &ranked) {
+                    exit_with(&format!("Cannot write {}: {}", path.display(), err));
+                }
+                println!("Saved the ranking to {}", path.display());
+            }
+        }
+        Command::Referee {
+            answer,
+            seed,
+            adversarial,
+            hard,
+            max_guesses,
+            player,
+            summary: summary_path,
+        } => {
+            let referee = Referee::new(Rules {
+                max_guesses,
+                hard_mode: hard,
+                ..Rules::default()
+            });
+            let policy = match answer {
+                Some(answer) => {
+                    let answer = answer.to_ascii_lowercase();
+                    if referee.check(&[], &answer).is_some() {
+                        exit_with(&format!("'{}' is not an allowed word", answer));
+                    }
+                    AnswerPolicy::Fixed(answer)
+                }
+                None if adversarial => AnswerPolicy::Adversarial,
+                None => AnswerPolicy::Random {
+                    seed: seed.unwrap_or_else(rand::random),
+                },
+            };
+
+            let summary = host(&referee, &policy, player);
+            if let AnswerPolicy::Random { seed } = policy {
+                eprintln!("seed {}", seed);
+            }
+            eprint!("{}", summary);
+            if let Some(path) = summary_path {
+                if let Err(err) = std::fs::write(&path, summary.to_string()) {
+                    exit_with(&format!("Cannot write {}: {}", path.display(), err));
+                }
+            }
+        }
+        #[cfg(feature = "server")]
+        Command::Serve { port } => {
+            let server = wordl::server::Server::bind(port).unwrap_or_else(|err| {
+                exit_with(&format!("Cannot listen on port {}: {}", port, err))
+            });
+            println!("Listening on http://{}", server.addr());
+            server.serve();
+        }
+        Command::Search { limit, query } => {
+            let query: Query = match query.join(" ").parse() {
+                Ok(query) => query,
+                Err(err) => exit_with(&format!("Invalid query: {}", err)),
+            };
+
+            let words = query.search(&wordl::algorithms::Utils::word_frequencies());
+            println!("{} matching words", words.len());
+            for (word, count) in words.iter().take(limit) {
+                println!("{} {}", word, count);
+            }
+        }
     }
 
     // }
@@ -104,3 +1139,40 @@ fn main() {
     //
     // println!("Guessed the ans in : {:?} moves", result);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The spec and lies `simulate-run` plays with for the arguments
+    fn simulate_run_lies(args: &[&str]) -> Result<(SolverSpec, Option<usize>), String> {
+        let args = ["wordl", "simulate-run"].iter().chain(args);
+        let Command::SimulateRun {
+            algorithm, lies, ..
+        } = Args::try_parse_from(args).unwrap().commands
+        else {
+            unreachable!("the command is simulate-run");
+        };
+        fibble_lies(&SolverRegistry::default(), algorithm, lies)
+    }
+
+    #[test]
+    fn test_simulate_run_lies() {
+        // other solvers play the Fibble games without being told the lies
+        let registry = SolverRegistry::default();
+        let (spec, lies) = simulate_run_lies(&["-a", "entropy", "--lies", "1"]).unwrap();
+        assert_eq!((spec.get("lies"), lies), (None, Some(1)));
+        assert!(registry.build(&spec).is_ok());
+
+        let (spec, lies) = simulate_run_lies(&["-a", "fibble", "--lies", "2"]).unwrap();
+        assert_eq!((spec.get("lies"), lies), (Some("2"), Some(2)));
+        let (spec, lies) = simulate_run_lies(&["-a", "fibble"]).unwrap();
+        assert_eq!((spec.get("lies"), lies), (Some("1"), Some(1)));
+        let (_, lies) = simulate_run_lies(&["-a", "fibble:lies=3"]).unwrap();
+        assert_eq!(lies, Some(3));
+        assert!(simulate_run_lies(&["-a", "fibble:lies=1", "--lies", "2"]).is_err());
+
+        let (_, lies) = simulate_run_lies(&["-a", "entropy"]).unwrap();
+        assert_eq!(lies, None);
+    }
+}
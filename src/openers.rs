@@ -0,0 +1,151 @@
+//! Ranking of the first guess of a game.
+//!
+//! Every allowed guess is scored as a first move against the candidates, and
+//! the best openers can be played through whole games to see how many moves a
+//! solver needs on average when it starts with them. The ranking can be saved
+//! to a cache file, one opener per line with the best first, so solvers can
+//! load the best opener without scoring everything again.
+
+use crate::{
+    algorithms::{GuessEvaluation, HighestEntropyAlgorithm},
+    Solver, Wordle,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::Path,
+};
+
+/// A first guess with how well it did
+#[derive(Debug, Clone, PartialEq)]
+pub struct Opener {
+    pub evaluation: GuessEvaluation,
+    /// Average number of moves of the simulated games, if they were played
+    pub average_moves: Option<f64>,
+}
+
+/// Scores every guess as a first move, the best first in the order the
+/// solvers rank guesses, see `GuessEvaluation::cmp_rank`
+pub fn rank<'a>(
+    guesses: impl Iterator<Item = &'a &'static str>,
+    candidates: &BTreeMap<&'static str, u32>,
+    weights: &HashMap<&'static str, f64>,
+) -> Vec<Opener> {
+    let guesses: Vec<&'static str> = guesses.copied().collect();
+    let mut openers: Vec<Opener> = GuessEvaluation::evaluate_all(&guesses, |guess| {
+        HighestEntropyAlgorithm::evaluate(guess, candidates, weights)
+    })
+    .into_iter()
+    .map(|evaluation| Opener {
+        evaluation,
+        average_moves: None,
+    })
+    .collect();
+
+    openers.sort_by(|a, b| a.evaluation.cmp_rank(&b.evaluation));
+    openers
+}
+
+/// Plays a game for every answer with the solver, reset between games, and
+/// returns the average number of moves, or why the solver failed or did not
+/// solve a game
+pub fn average_moves<S: Solver>(
+    wordle: &Wordle,
+    answers: &[&'static str],
+    mut solver: S,
+) -> Result<f64, String> {
+    let mut moves = 0;
+    for answer in answers {
+        solver.reset();
+        moves += wordle
+            .simulate(answer, &mut solver, Wordle::MAX_SIMULATED_GUESSES)
+            .map_err(|err| format!("the game for '{}' stopped: {}", answer, err))?
+            .len();
+    }
+    Ok(moves as f64 / answers.len() as f64)
+}
+
+/// Writes the ranking to a cache file, the best opener first
+pub fn save(path: impl AsRef<Path>, openers: &[Opener]) -> io::Result<()> {
+    let mut contents = String::from("# word entropy expected_remaining worst_case average_moves\n");
+    for opener in openers {
+        let evaluation = &opener.evaluation;
+        let average_moves = opener
+            .average_moves
+            .map_or_else(|| "-".to_string(), |moves| moves.to_string());
+        contents.push_str(&format!(
+            "{} {} {} {} {}\n",
+            evaluation.word,
+            evaluation.entropy,
+            evaluation.expected_remaining,
+            evaluation.worst_case,
+            average_moves
+        ));
+    }
+    fs::write(path, contents)
+}
+
+/// Reads the best opener from a cache file written by `save`
+pub fn load_best(path: impl AsRef<Path>) -> io::Result<String> {
+    fs::read_to_string(path)?
+        .lines()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .and_then(|line| line.split_whitespace().next())
+        .map(|word| word.to_string())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "the opener cache is empty"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{Prior, WithOpener};
+
+    fn answers() -> BTreeMap<&'static str, u32> {
+        include_str!("../dataset/wordle/ans.txt")
+            .lines()
+            .map(|word| (word.trim(), 1))
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_sorts_by_entropy() {
+        let candidates = answers();
+        let weights = Prior::Uniform.weights(&candidates);
+        let guesses = ["fuzzy", "raise", "tares", "qajaq"];
+
+        let ranked = rank(guesses.iter(), &candidates, &weights);
+        let words: Vec<_> = ranked.iter().map(|o| o.evaluation.word.as_ref()).collect();
+        assert_eq!(words.first(), Some(&"raise"));
+        assert_eq!(words.last(), Some(&"qajaq"));
+        assert!(ranked
+            .windows(2)
+            .all(|w| w[0].evaluation.cmp_rank(&w[1].evaluation).is_lt()));
+    }
+
+    #[test]
+    fn test_save_and_load_best() {
+        let candidates = answers();
+        let weights = Prior::Uniform.weights(&candidates);
+        let guesses = ["fuzzy", "raise"];
+        let mut ranked = rank(guesses.iter(), &candidates, &weights);
+        ranked[0].average_moves = Some(3.5);
+
+        let path = std::env::temp_dir().join(format!("wordl-openers-{}.txt", std::process::id()));
+        save(&path, &ranked).unwrap();
+        assert_eq!(load_best(&path).unwrap(), "raise");
+
+        fs::write(&path, "# nothing here\n").unwrap();
+        assert!(load_best(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_average_moves() {
+        let wordle = Wordle::new();
+        let solver = HighestEntropyAlgorithm::new().with_opener("raise").unwrap();
+        // the solver plays the same games every time, 7 moves for the two
+        let average = average_moves(&wordle, &["sauce", "wrung"], solver).unwrap();
+        assert_eq!(average, 3.5);
+    }
+}
@@ -0,0 +1,321 @@
+//! Python module built with PyO3, for looking at the solvers from notebooks.
+//!
+//! Histories are lists of `(word, pattern)` tuples, e.g.
+//! `[("tares", "IMMII"), ("grail", "MMMMI")]`, the way `Game.history` gives
+//! them back.
+//!
+//! ```python
+//! import wordl
+//! solver = wordl.Solver("entropy:prior=sigmoid")
+//! solver.suggest([("tares", "IMMII")])
+//! results = wordl.benchmark("optimized", count=100)
+//! ```
+
+use crate::{
+    algorithms::SolverRegistry,
+    referee::{self, Referee, Rules},
+    Attempt, Score, Solver as _, Wordle,
+};
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+type History = Vec<(String, String)>;
+
+fn parse_history(history: History) -> PyResult<Vec<Attempt<'static>>> {
+    history
+        .into_iter()
+        .map(|(word, pattern)| {
+            format!("{}:{}", word, pattern)
+                .parse()
+                .map_err(PyValueError::new_err)
+        })
+        .collect()
+}
+
+fn to_history(attempts: &[Attempt]) -> History {
+    attempts
+        .iter()
+        .map(|attempt| (attempt.word.to_string(), Score::to_pattern(&attempt.score)))
+        .collect()
+}
+
+fn check_word(word: &str) -> PyResult<String> {
+    Referee::normalize(word).map_err(PyValueError::new_err)
+}
+
+/// The pattern the guess gets against the answer, e.g. `IMMII`
+#[pyfunction]
+fn color(answer: &str, guess: &str) -> PyResult<String> {
+    let score = Score::color(&check_word(answer)?, &check_word(guess)?);
+    Ok(Score::to_pattern(&score))
+}
+
+/// A game against a given answer, or one picked with the seed
+#[pyclass]
+struct Game {
+    referee: Referee,
+    game: referee::Game,
+}
+
+#[pymethods]
+impl Game {
+    #[new]
+    #[pyo3(signature = (answer=None, seed=None, max_guesses=6, hard=false))]
+    fn new(
+        answer: Option<&str>,
+        seed: Option<u64>,
+        max_guesses: usize,
+        hard: bool,
+    ) -> PyResult<Self> {
+        let referee = Referee::default();
+        let rules = Rules {
+            max_guesses,
+            hard_mode: hard,
+            ..Rules::default()
+        };
+        let answer = match answer {
+            Some(answer) => {
+                let answer = check_word(answer)?;
+                referee.word(&answer).ok_or_else(|| {
+                    PyValueError::new_err(format!("'{}' is not in the dictionary", answer))
+                })?
+            }
+            None => referee.random_answer(seed.unwrap_or_else(rand::random)),
+        };
+
+        let game = referee::Game::new(answer, rules);
+        Ok(Self { referee, game })
+    }
+
+    /// Plays the guess and returns its pattern
+    fn guess(&mut self, word: &str) -> PyResult<String> {
+        let score = self
+            .game
+            .guess(&self.referee, word)
+            .map_err(PyValueError::new_err)?;
+        Ok(Score::to_pattern(&score))
+    }
+
+    #[getter]
+    fn won(&self) -> bool {
+        self.game.won()
+    }
+
+    #[getter]
+    fn over(&self) -> bool {
+        self.game.is_over()
+    }
+
+    #[getter]
+    fn history(&self) -> History {
+        to_history(&self.game.attempts)
+    }
+
+    /// The answer, once the game is over
+    #[getter]
+    fn answer(&self) -> Option<String> {
+        self.over().then(|| self.game.answer.to_string())
+    }
+}
+
+/// Any solver of the `SolverRegistry`, by spec, e.g. `entropy:prior=sigmoid`
+#[pyclass(unsendable)]
+struct Solver {
+    solver: Box<dyn crate::Solver>,
+}
+
+#[pymethods]
+impl Solver {
+    #[new]
+    #[pyo3(signature = (spec="entropy"))]
+    fn new(spec: &str) -> PyResult<Self> {
+        let solver = SolverRegistry::default()
+            .create(spec)
+            .map_err(PyValueError::new_err)?;
+        Ok(Self { solver })
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        self.solver.name().to_string()
+    }
+
+    /// The next guess of the solver
+    fn suggest(&mut self, history: History) -> PyResult<String> {
+        let history = parse_history(history)?;
+        let mut ranked = self.solver.rank(&history, 1);
+        match ranked.pop() {
+            Some(evaluation) => Ok(evaluation.word.into_owned()),
+            None => Err(PyValueError::new_err("no word fits the history")),
+        }
+    }
+
+    /// The `n` best next guesses with their scores, best first
+    #[pyo3(signature = (history, n=5))]
+    fn rank<'py>(
+        &mut self,
+        py: Python<'py>,
+        history: History,
+        n: usize,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let history = parse_history(history)?;
+        self.solver
+            .rank(&history, n)
+            .into_iter()
+            .map(|evaluation| {
+                let dict = PyDict::new(py);
+                dict.set_item("word", evaluation.word.as_ref())?;
+                dict.set_item("score", evaluation.score)?;
+                dict.set_item("entropy", evaluation.entropy)?;
+                dict.set_item("expected_remaining", evaluation.expected_remaining)?;
+                dict.set_item("worst_case", evaluation.worst_case)?;
+                dict.set_item("win_probability", evaluation.win_probability)?;
+                dict.set_item("is_candidate", evaluation.is_candidate)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    fn reset(&mut self) {
+        self.solver.reset();
+    }
+}
+
+/// Plays a game for every answer, the first `count` of the answer list by
+/// default, and returns one dict per game with the `answer`, the `guesses`
+/// and the number of `moves`. Raises a RuntimeError naming the answer when
+/// the solver fails a game or does not solve it.
+#[pyfunction]
+#[pyo3(signature = (spec="entropy", answers=None, count=None))]
+fn benchmark<'py>(
+    py: Python<'py>,
+    spec: &str,
+    answers: Option<Vec<String>>,
+    count: Option<usize>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let mut solver = SolverRegistry::default()
+        .create(spec)
+        .map_err(PyValueError::new_err)?;
+    let wordle = Wordle::new();
+    let answers: Vec<&'static str> = match answers {
+        Some(answers) => answers
+            .iter()
+            .map(|answer| {
+                let answer = check_word(answer)?;
+                wordle
+                    .dictionary
+                    .get(answer.as_str())
+                    .copied()
+                    .ok_or_else(|| PyValueError::new_err(format!("'{}' is not a word", answer)))
+            })
+            .collect::<PyResult<_>>()?,
+        None => include_str!("../dataset/wordle/ans.txt")
+            .lines()
+            .map(|word| word.trim())
+            .collect(),
+    };
+
+    answers
+        .into_iter()
+        .take(count.unwrap_or(usize::MAX))
+        .map(|answer| {
+            solver.reset();
+            let history = wordle
+                .simulate(answer, &mut solver, Wordle::MAX_SIMULATED_GUESSES)
+                .map_err(|err| {
+                    PyRuntimeError::new_err(format!("the game for '{}' stopped: {}", answer, err))
+                })?;
+            let guesses: Vec<String> = history.iter().map(|a| a.word.to_string()).collect();
+
+            let dict = PyDict::new(py);
+            dict.set_item("answer", answer)?;
+            dict.set_item("moves", guesses.len())?;
+            dict.set_item("guesses", guesses)?;
+            Ok(dict)
+        })
+        .collect()
+}
+
+#[pymodule]
+fn wordl(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(color, module)?)?;
+    module.add_function(wrap_pyfunction!(benchmark, module)?)?;
+    module.add_class::<Game>()?;
+    module.add_class::<Solver>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(attempts: &[(&str, &str)]) -> History {
+        attempts
+            .iter()
+            .map(|(word, pattern)| (word.to_string(), pattern.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_color_and_game() {
+        assert_eq!(color("cigar", "TARES").unwrap(), "IMMII");
+        assert!(color("cigar", "tar").is_err());
+
+        let mut game = Game::new(Some("cigar"), None, 6, true).unwrap();
+        assert_eq!(game.guess("tares").unwrap(), "IMMII");
+        assert!(game.guess("plumb").is_err());
+        assert!(game.answer().is_none());
+        assert_eq!(game.guess("cigar").unwrap(), "CCCCC");
+        assert!(game.won() && game.over());
+        assert_eq!(game.answer().as_deref(), Some("cigar"));
+        assert_eq!(
+            game.history(),
+            history(&[("tares", "IMMII"), ("cigar", "CCCCC")])
+        );
+        assert!(Game::new(Some("xyzzy"), None, 6, false).is_err());
+    }
+
+    #[test]
+    fn test_solver_and_benchmark() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let mut solver = Solver::new("entropy:opener=crane").unwrap();
+            assert_eq!(solver.name(), "entropy");
+            assert_eq!(solver.suggest(Vec::new()).unwrap(), "crane");
+
+            let ranked = solver
+                .rank(py, history(&[("tares", "IMMII"), ("grail", "MMMMI")]), 2)
+                .unwrap();
+            assert!(!ranked.is_empty() && ranked.len() <= 2);
+            let entropy: f64 = ranked[0]
+                .get_item("entropy")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(entropy >= 0.0);
+            assert!(solver.suggest(history(&[("tares", "XXXXX")])).is_err());
+            assert!(Solver::new("genius").is_err());
+
+            let answers = Some(vec!["cigar".to_string(), "rebut".to_string()]);
+            let results = benchmark(py, "entropy", answers, None).unwrap();
+            assert_eq!(results.len(), 2);
+            let moves: usize = results[0]
+                .get_item("moves")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            let guesses: Vec<String> = results[0]
+                .get_item("guesses")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(moves, guesses.len());
+            assert_eq!(guesses.last().map(String::as_str), Some("cigar"));
+        });
+    }
+}
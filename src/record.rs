@@ -0,0 +1,236 @@
+//! Games saved as JSON, to be replayed or studied later.
+//!
+//! A record file holds one game per line, so the games of a whole benchmark
+//! run can be appended to the same file. The attempts are written the way
+//! `Attempt` displays them, as serde writes any attempt:
+//!
+//! ```json
+//! {"answer":"cigar","rules":{"max_guesses":6,"hard_mode":false,"max_invalid":10},
+//!  "lexicon":"ngram-3e6f0475","attempts":["tares:IMMII","cigar:CCCCC"],
+//!  "solver":"entropy","started_at":1718755200,"finished_at":1718755201}
+//! ```
+//!
+//! A Fibble game also has `"lies":1`, the number of wrong tiles of its rows.
+
+use crate::{
+    algorithms::{Constraints, Utils},
+    referee::Rules,
+    search::Query,
+    Attempt, Score,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A game, who played it and with which words
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub answer: String,
+    pub rules: Rules,
+    /// Number of lies per row of a Fibble game, none for a classic one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lies: Option<usize>,
+    /// The lexicon the game was played with, see `lexicon_id`
+    pub lexicon: String,
+    pub attempts: Vec<Attempt<'static>>,
+    /// Name of the solver that played, none for a person
+    pub solver: Option<String>,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+    pub finished_at: u64,
+}
+
+/// Identifies the word list and n-gram counts the solvers use, so a record
+/// can tell when it is replayed against other words
+pub fn lexicon_id() -> String {
+    let hash = Utils::fnv1a_32(include_str!("../dataset/wordle_words_x_n_gram.txt").bytes());
+    format!("ngram-{:08x}", hash)
+}
+
+/// Seconds since the Unix epoch, as records keep time
+pub fn timestamp(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+fn now() -> u64 {
+    timestamp(SystemTime::now())
+}
+
+impl GameRecord {
+    /// A game starting now, without attempts
+    pub fn new(answer: impl Into<String>, rules: Rules, solver: Option<String>) -> Self {
+        let started_at = now();
+        Self {
+            answer: answer.into(),
+            rules,
+            lies: None,
+            lexicon: lexicon_id(),
+            attempts: Vec::new(),
+            solver,
+            started_at,
+            finished_at: started_at,
+        }
+    }
+
+    /// Sets the attempts of the game and marks it finished now
+    pub fn finish(mut self, attempts: Vec<Attempt<'static>>) -> Self {
+        self.attempts = attempts;
+        self.finished_at = now();
+        self
+    }
+
+    pub fn won(&self) -> bool {
+        self.attempts
+            .last()
+            .is_some_and(|attempt| attempt.score == [Score::Correct; 5])
+    }
+
+    /// The words of the lexicon that fit the first `guesses` attempts, most
+    /// frequent first. The rows of a Fibble game are told with its lies, so
+    /// they fit with exactly that many tiles off except the winning one.
+    pub fn candidates(
+        &self,
+        guesses: usize,
+        lexicon: &BTreeMap<&'static str, u32>,
+    ) -> Vec<(&'static str, u32)> {
+        let attempts = &self.attempts[..guesses.min(self.attempts.len())];
+        let Some(lies) = self.lies else {
+            let query = Query {
+                constraints: Constraints::from_attempts(attempts),
+            };
+            return query.search(lexicon);
+        };
+
+        let mut words: Vec<_> = lexicon
+            .iter()
+            .filter(|(&word, _)| {
+                attempts.iter().all(|attempt| {
+                    if attempt.score == [Score::Correct; 5] {
+                        attempt.word == word
+                    } else {
+                        attempt.word != word && Utils::mismatches(attempt, word) == lies
+                    }
+                })
+            })
+            .map(|(&word, &count)| (word, count))
+            .collect();
+        words.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        words
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a record is always valid JSON")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|err| err.to_string())
+    }
+
+    /// Appends the records to the file, one per line
+    pub fn append(path: impl AsRef<Path>, records: &[GameRecord]) -> io::Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        let mut lines = String::new();
+        for record in records {
+            lines.push_str(&record.to_json());
+            lines.push('\n');
+        }
+        file.write_all(lines.as_bytes())
+    }
+
+    /// Reads every record of the file
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Vec<GameRecord>> {
+        fs::read_to_string(path)?
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(number, line)| {
+                GameRecord::from_json(line).map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("line {}: {}", number + 1, err),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> GameRecord {
+        let attempts = vec![
+            "tares:IMMII".parse().unwrap(),
+            "cigar:CCCCC".parse().unwrap(),
+        ];
+        GameRecord::new("cigar", Rules::default(), Some("entropy".to_string())).finish(attempts)
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let record = record();
+        assert!(record.won());
+        let json = record.to_json();
+        assert!(json.contains(r#""attempts":["tares:IMMII","cigar:CCCCC"]"#));
+        assert_eq!(GameRecord::from_json(&json).unwrap(), record);
+
+        let broken = json.replace("IMMII", "IMMIX");
+        assert!(GameRecord::from_json(&broken).is_err());
+        assert_eq!(lexicon_id(), "ngram-3e6f0475");
+        assert!(!json.contains("lies"));
+    }
+
+    #[test]
+    fn test_fibble_candidates() {
+        // the first row lies on its last tile, cigar gives IMMII to tares
+        let attempts = vec![
+            "tares:IMMIC".parse().unwrap(),
+            "cigar:CCCCC".parse().unwrap(),
+        ];
+        let record = GameRecord {
+            lies: Some(1),
+            ..GameRecord::new("cigar", Rules::default(), None).finish(attempts)
+        };
+        let json = record.to_json();
+        assert!(json.contains(r#""lies":1"#));
+        assert_eq!(GameRecord::from_json(&json).unwrap(), record);
+
+        let lexicon = Utils::word_frequencies();
+        let words = |record: &GameRecord, guesses| -> Vec<&str> {
+            record
+                .candidates(guesses, &lexicon)
+                .into_iter()
+                .map(|(word, _)| word)
+                .collect()
+        };
+        assert!(words(&record, 1).contains(&"cigar"));
+        assert_eq!(words(&record, 2), ["cigar"]);
+
+        // read as a classic game the lying row rules the answer out
+        let classic = GameRecord {
+            lies: None,
+            ..record
+        };
+        assert!(!words(&classic, 1).contains(&"cigar"));
+    }
+
+    #[test]
+    fn test_append_and_load() {
+        let path = std::env::temp_dir().join(format!("wordl-records-{}", std::process::id()));
+        let records = [record(), GameRecord::new("rebut", Rules::default(), None)];
+        GameRecord::append(&path, &records[..1]).unwrap();
+        GameRecord::append(&path, &records[1..]).unwrap();
+        assert_eq!(GameRecord::load(&path).unwrap(), records);
+        fs::remove_file(&path).unwrap();
+    }
+}
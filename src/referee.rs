@@ -0,0 +1,464 @@
+//! Hosts a game for a player talking over a pair of streams, usually the
+//! stdin and stdout of another program.
+//!
+//! The protocol is line based, `>` is what the referee writes and `<` what
+//! the player answers:
+//!
+//! ```text
+//! > start 6 normal
+//! < tares
+//! > IMMII
+//! < xyzzy
+//! > invalid not in the dictionary
+//! < cigar
+//! > win 2 cigar
+//! ```
+//!
+//! - `start <max guesses> <normal|hard>` opens the game.
+//! - The player answers every line but the last with a guess.
+//! - A valid guess gets its feedback, `C` for correct, `M` for misplaced and
+//!   `I` for incorrect.
+//! - A guess that is not allowed gets `invalid <reason>` and does not use a
+//!   turn, but the game is lost after too many of them.
+//! - The game ends with `win <guesses> <answer>` or `lose <answer>`. It is
+//!   lost as well when the player stops answering.
+//!
+//! In hard mode every guess has to keep the green letters in place and use
+//! every letter that was found so far.
+
+use crate::{Attempt, Score, Wordle};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    borrow::Cow,
+    cmp::Reverse,
+    fmt,
+    io::{self, BufRead, Write},
+};
+
+/// How the referee picks the answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnswerPolicy {
+    /// Always the same answer
+    Fixed(String),
+    /// An answer of the answer list picked with the seed
+    Random { seed: u64 },
+    /// No answer is picked up front, every guess gets the feedback that keeps
+    /// the most answers possible
+    Adversarial,
+}
+
+/// The rules of a refereed game
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rules {
+    pub max_guesses: usize,
+    pub hard_mode: bool,
+    /// Number of invalid guesses after which the game is lost
+    pub max_invalid: usize,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            max_guesses: 6,
+            hard_mode: false,
+            max_invalid: 10,
+        }
+    }
+}
+
+/// How a refereed game went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    /// The answer, for an adversarial game the one left when the game ended
+    pub answer: String,
+    pub attempts: Vec<Attempt<'static>>,
+    pub invalid: usize,
+    pub won: bool,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let result = if self.won { "won" } else { "lost" };
+        writeln!(
+            f,
+            "{} in {} guesses, answer {}, {} invalid guesses",
+            result,
+            self.attempts.len(),
+            self.answer,
+            self.invalid
+        )?;
+        for attempt in &self.attempts {
+            writeln!(f, "{} {}", attempt.word, Score::to_pattern(&attempt.score))?;
+        }
+        Ok(())
+    }
+}
+
+/// A game played one guess at a time against an answer picked up front, for
+/// the bindings and the server that keep it between calls
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub answer: &'static str,
+    pub rules: Rules,
+    pub attempts: Vec<Attempt<'static>>,
+}
+
+impl Game {
+    pub fn new(answer: &'static str, rules: Rules) -> Self {
+        Self {
+            answer,
+            rules,
+            attempts: Vec::new(),
+        }
+    }
+
+    pub fn won(&self) -> bool {
+        self.attempts
+            .last()
+            .is_some_and(|attempt| attempt.score == [Score::Correct; 5])
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.won() || self.attempts.len() >= self.rules.max_guesses
+    }
+
+    /// Plays the guess and returns its feedback, or why the guess is not
+    /// allowed. The referee knows the words, the rules are those of the game.
+    pub fn guess(&mut self, referee: &Referee, word: &str) -> Result<[Score; 5], String> {
+        if self.is_over() {
+            return Err("the game is over".to_string());
+        }
+        let word = Referee::normalize(word)?;
+        if let Some(reason) = referee.check_with(&self.rules, &self.attempts, &word) {
+            return Err(reason);
+        }
+
+        let score = Score::color(self.answer, &word);
+        self.attempts.push(Attempt {
+            word: Cow::Owned(word),
+            score,
+        });
+        Ok(score)
+    }
+}
+
+pub struct Referee {
+    wordle: Wordle,
+    /// The words that can be the answer
+    answers: Vec<&'static str>,
+    pub rules: Rules,
+}
+
+impl Default for Referee {
+    fn default() -> Self {
+        Self::new(Rules::default())
+    }
+}
+
+impl Referee {
+    pub fn new(rules: Rules) -> Self {
+        let answers = include_str!("../dataset/wordle/ans.txt")
+            .lines()
+            .map(|word| word.trim())
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        Self {
+            wordle: Wordle::new(),
+            answers,
+            rules,
+        }
+    }
+
+    /// The word in lower case, or why it is not a five letter word
+    pub fn normalize(word: &str) -> Result<String, String> {
+        let word = word.trim().to_ascii_lowercase();
+        if word.len() != 5 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+            return Err(format!("'{}' is not a five letter word", word));
+        }
+        Ok(word)
+    }
+
+    /// The word as it is in the dictionary, if it is there
+    pub fn word(&self, word: &str) -> Option<&'static str> {
+        self.wordle.dictionary.get(word).copied()
+    }
+
+    /// Why the guess is not allowed after the attempts, if it is not
+    pub fn check(&self, attempts: &[Attempt], guess: &str) -> Option<String> {
+        self.check_with(&self.rules, attempts, guess)
+    }
+
+    /// Like `check`, under other rules than those of the referee
+    pub fn check_with(&self, rules: &Rules, attempts: &[Attempt], guess: &str) -> Option<String> {
+        if self.word(guess).is_none() {
+            return Some("not in the dictionary".to_string());
+        }
+        if rules.hard_mode {
+            return Referee::hard_mode_violation(attempts, guess);
+        }
+        None
+    }
+
+    /// Greens have to stay in place and every letter found has to be used
+    pub fn hard_mode_violation(attempts: &[Attempt], guess: &str) -> Option<String> {
+        let guess = guess.as_bytes();
+        for attempt in attempts {
+            let word = attempt.word.as_bytes();
+            for (i, score) in attempt.score.iter().enumerate() {
+                if *score == Score::Correct && guess[i] != word[i] {
+                    return Some(format!("letter {} must be {}", i + 1, word[i] as char));
+                }
+            }
+
+            for (i, score) in attempt.score.iter().enumerate() {
+                let letter = word[i];
+                let found = word
+                    .iter()
+                    .zip(&attempt.score)
+                    .filter(|(&c, &s)| c == letter && s != Score::Incorrect)
+                    .count();
+                let used = guess.iter().filter(|&&c| c == letter).count();
+                if *score == Score::Misplaced && used < found {
+                    return Some(format!("guess must contain {}", letter as char));
+                }
+            }
+        }
+        None
+    }
+
+    /// The answer of the answer list picked with the seed
+    pub fn random_answer(&self, seed: u64) -> &'static str {
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.answers[rng.gen_range(0..self.answers.len())]
+    }
+
+    /// Plays one game with the player on the other side of the streams
+    pub fn play<R: BufRead, W: Write>(
+        &self,
+        policy: &AnswerPolicy,
+        mut input: R,
+        mut output: W,
+    ) -> io::Result<Summary> {
+        let mut candidates: Vec<&str> = match policy {
+            AnswerPolicy::Fixed(answer) => vec![answer.as_str()],
+            AnswerPolicy::Random { seed } => vec![self.random_answer(*seed)],
+            AnswerPolicy::Adversarial => self.answers.clone(),
+        };
+
+        let mode = if self.rules.hard_mode {
+            "hard"
+        } else {
+            "normal"
+        };
+        writeln!(output, "start {} {}", self.rules.max_guesses, mode)?;
+        output.flush()?;
+
+        let mut summary = Summary {
+            answer: candidates[0].to_string(),
+            attempts: Vec::new(),
+            invalid: 0,
+            won: false,
+        };
+        let mut line = String::new();
+        while summary.attempts.len() < self.rules.max_guesses
+            && summary.invalid < self.rules.max_invalid
+        {
+            line.clear();
+            if input.read_line(&mut line)? == 0 {
+                break;
+            }
+
+            let guess = line.trim().to_ascii_lowercase();
+            if let Some(reason) = self.check(&summary.attempts, &guess) {
+                summary.invalid += 1;
+                writeln!(output, "invalid {}", reason)?;
+                output.flush()?;
+                continue;
+            }
+
+            let score = Referee::feedback(&mut candidates, &guess);
+            summary.answer = candidates[0].to_string();
+            writeln!(output, "{}", Score::to_pattern(&score))?;
+            output.flush()?;
+            summary.attempts.push(Attempt {
+                word: Cow::Owned(guess),
+                score,
+            });
+
+            if score == [Score::Correct; 5] {
+                summary.won = true;
+                break;
+            }
+        }
+
+        // the game is decided, a player that left already does not change that
+        let _ = if summary.won {
+            writeln!(output, "win {} {}", summary.attempts.len(), summary.answer)
+        } else {
+            writeln!(output, "lose {}", summary.answer)
+        }
+        .and_then(|_| output.flush());
+        Ok(summary)
+    }
+
+    /// The feedback to the guess, keeping only the candidates that would
+    /// have given it. With more than one candidate the feedback that keeps
+    /// the most of them is picked, ties going to the one with the fewest
+    /// correct and misplaced letters, then to the highest pattern index.
+    fn feedback(candidates: &mut Vec<&str>, guess: &str) -> [Score; 5] {
+        let mut buckets = vec![0usize; 243];
+        for candidate in candidates.iter() {
+            buckets[Score::index(&Score::color(candidate, guess))] += 1;
+        }
+
+        let hits = |index| {
+            Score::from_index(index)
+                .iter()
+                .filter(|&&score| score != Score::Incorrect)
+                .count()
+        };
+        let (index, _) = buckets
+            .iter()
+            .enumerate()
+            .max_by_key(|&(index, &count)| (count, Reverse(hits(index)), index))
+            .expect("there are 243 patterns");
+        candidates.retain(|candidate| Score::index(&Score::color(candidate, guess)) == index);
+        Score::from_index(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(referee: &Referee, policy: &AnswerPolicy, player: &str) -> (Summary, String) {
+        let mut output = Vec::new();
+        let summary = referee
+            .play(policy, player.as_bytes(), &mut output)
+            .unwrap();
+        (summary, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_plays_the_protocol() {
+        let referee = Referee::default();
+        let policy = AnswerPolicy::Fixed("cigar".to_string());
+        let (summary, output) = play(&referee, &policy, "tares\nxyzzy\ncigar\n");
+
+        assert_eq!(
+            output,
+            "start 6 normal\nIMMII\ninvalid not in the dictionary\nCCCCC\nwin 2 cigar\n"
+        );
+        assert!(summary.won);
+        assert_eq!(summary.attempts.len(), 2);
+        assert_eq!(summary.invalid, 1);
+    }
+
+    #[test]
+    fn test_loses_after_the_guess_limit_or_at_the_end_of_input() {
+        let referee = Referee::new(Rules {
+            max_guesses: 2,
+            ..Rules::default()
+        });
+        let policy = AnswerPolicy::Fixed("cigar".to_string());
+
+        let (summary, output) = play(&referee, &policy, "tares\ncrane\ncigar\n");
+        assert!(!summary.won);
+        assert!(output.ends_with("lose cigar\n"));
+
+        let (summary, _) = play(&referee, &policy, "tares\n");
+        assert!(!summary.won);
+        assert_eq!(summary.attempts.len(), 1);
+    }
+
+    #[test]
+    fn test_hard_mode() {
+        let referee = Referee::new(Rules {
+            hard_mode: true,
+            ..Rules::default()
+        });
+        let history = [Attempt {
+            word: Cow::Borrowed("cider"),
+            score: Score::color("cigar", "cider"),
+        }];
+        assert!(referee.check(&history, "cigar").is_none());
+        // the I has to stay second
+        assert!(referee.check(&history, "caper").is_some());
+
+        let history = [Attempt {
+            word: Cow::Borrowed("tares"),
+            score: Score::color("cigar", "tares"),
+        }];
+        assert!(referee.check(&history, "roast").is_none());
+        // the R was found and has to be used
+        assert!(referee.check(&history, "plaid").is_some());
+        assert!(Referee::default().check(&history, "plaid").is_none());
+    }
+
+    #[test]
+    fn test_game_checks_and_ends() {
+        let referee = Referee::default();
+        assert_eq!(Referee::normalize(" Cigar "), Ok("cigar".to_string()));
+        assert!(Referee::normalize("cig4r").is_err());
+
+        let rules = Rules {
+            max_guesses: 2,
+            hard_mode: true,
+            ..Rules::default()
+        };
+        let mut game = Game::new("cigar", rules);
+        assert!(game.guess(&referee, "xyzzy").is_err());
+        assert_eq!(
+            game.guess(&referee, "TARES"),
+            Ok(Score::color("cigar", "tares"))
+        );
+        // the R and the A were found and have to be used
+        assert!(game.guess(&referee, "plumb").is_err());
+        assert!(!game.is_over());
+        game.guess(&referee, "roast").unwrap();
+        assert!(game.is_over() && !game.won());
+        assert!(game.guess(&referee, "cigar").is_err());
+    }
+
+    #[test]
+    fn test_random_answers_follow_the_seed() {
+        let referee = Referee::default();
+        let policy = AnswerPolicy::Random { seed: 7 };
+        let (first, _) = play(&referee, &policy, "tares\n");
+        let (second, _) = play(&referee, &policy, "tares\n");
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_adversarial_answer_avoids_the_guesses() {
+        let referee = Referee::default();
+        let (summary, _) = play(&referee, &AnswerPolicy::Adversarial, "cigar\nrebut\n");
+        assert!(!summary.won);
+        assert!(summary
+            .attempts
+            .iter()
+            .all(|a| a.score != [Score::Correct; 5]));
+        assert_ne!(summary.answer, "cigar");
+        assert_ne!(summary.answer, "rebut");
+    }
+
+    #[test]
+    fn test_adversarial_ties_give_the_fewest_hits() {
+        // MIIII has a lower index than IICCI but shows less of the answer
+        let mut candidates = vec!["xxcdx", "xxxxa"];
+        let score = Referee::feedback(&mut candidates, "abcde");
+        assert_eq!(Score::to_pattern(&score), "MIIII");
+        assert_eq!(candidates, ["xxxxa"]);
+
+        let mut candidates = vec!["edxxx", "xxxdx"];
+        let score = Referee::feedback(&mut candidates, "abcde");
+        assert_eq!(Score::to_pattern(&score), "IIICI");
+
+        // with as many hits the highest index wins
+        let mut candidates = vec!["xxxdx", "xxxxe"];
+        let score = Referee::feedback(&mut candidates, "abcde");
+        assert_eq!(Score::to_pattern(&score), "IIIIC");
+    }
+}
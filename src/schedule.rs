@@ -0,0 +1,215 @@
+//! The daily puzzles: puzzle 0 came out on 2021-06-19 and there has been one a
+//! day since.
+//!
+//! The answers of the puzzles come from a schedule file with one answer per
+//! line in the order they were played, since `dataset/wordle/ans.txt` is
+//! sorted alphabetically.
+
+use crate::algorithms::Utils;
+use std::{fmt, fs, io, ops::Range, path::Path, str::FromStr};
+
+/// A day of the Gregorian calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        let date = Self { year, month, day };
+        (Date::from_days(date.days()) == date).then_some(date)
+    }
+
+    /// Days since 1970-01-01, negative before
+    pub fn days(&self) -> i64 {
+        // days_from_civil of Howard Hinnant's date algorithms
+        let year = self.year as i64 - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146097 + day_of_era - 719468
+    }
+
+    /// The date that many days after 1970-01-01
+    pub fn from_days(days: i64) -> Self {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        Self {
+            year: (year_of_era + era * 400 + (month <= 2) as i64) as i32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    /// Today in UTC
+    pub fn today() -> Self {
+        let seconds = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        Date::from_days((seconds / 86400) as i64)
+    }
+}
+
+/// Writes the date as `YYYY-MM-DD`
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split('-').collect();
+        let date = match parts[..] {
+            [year, month, day] => match (year.parse(), month.parse(), day.parse()) {
+                (Ok(year), Ok(month), Ok(day)) => Date::new(year, month, day),
+                _ => None,
+            },
+            _ => None,
+        };
+        date.ok_or_else(|| format!("'{}' is not a date like 2022-01-15", s))
+    }
+}
+
+/// The answers of the daily puzzles in the order they were played
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    answers: Vec<&'static str>,
+}
+
+impl Schedule {
+    /// The day of puzzle 0
+    pub const FIRST_DAY: Date = Date {
+        year: 2021,
+        month: 6,
+        day: 19,
+    };
+
+    /// A schedule of the answers, which have to be words of the lexicon
+    pub fn new<'a>(answers: impl IntoIterator<Item = &'a str>) -> Result<Self, String> {
+        let lexicon = Utils::word_frequencies();
+        let answers = answers
+            .into_iter()
+            .map(|answer| {
+                let answer = answer.trim().to_ascii_lowercase();
+                lexicon
+                    .get_key_value(answer.as_str())
+                    .map(|(&word, _)| word)
+                    .ok_or_else(|| format!("'{}' is not in the lexicon", answer))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { answers })
+    }
+
+    /// Reads a schedule file, one answer per line, blank lines and lines
+    /// starting with `#` are skipped
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let answers = contents
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        Schedule::new(answers).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Number of puzzles in the schedule
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+
+    /// The number of the puzzle of the day, none before the first one
+    pub fn number(date: Date) -> Option<usize> {
+        usize::try_from(date.days() - Schedule::FIRST_DAY.days()).ok()
+    }
+
+    /// The day of the puzzle, none when its year is out of range
+    pub fn date(number: usize) -> Option<Date> {
+        let days = i64::try_from(number)
+            .ok()?
+            .checked_add(Schedule::FIRST_DAY.days())?;
+        // a year has at least 365 days, so this bounds the year from above
+        (days / 365 < i64::from(i32::MAX) - 1970).then(|| Date::from_days(days))
+    }
+
+    /// The answer of the puzzle, none past the end of the schedule
+    pub fn answer(&self, number: usize) -> Option<&'static str> {
+        self.answers.get(number).copied()
+    }
+
+    /// The answer of the puzzle of the day
+    pub fn answer_on(&self, date: Date) -> Option<&'static str> {
+        Schedule::number(date).and_then(|number| self.answer(number))
+    }
+
+    /// The answers of the puzzles in the range, cut at the end of the
+    /// schedule
+    pub fn answers(&self, range: Range<usize>) -> &[&'static str] {
+        let end = range.end.min(self.answers.len());
+        &self.answers[range.start.min(end)..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dates() {
+        let date: Date = "2022-01-15".parse().unwrap();
+        assert_eq!(date.to_string(), "2022-01-15");
+        assert_eq!(Date::from_days(date.days()), date);
+        assert_eq!(Date::new(1970, 1, 1).unwrap().days(), 0);
+        assert_eq!(Date::new(2000, 3, 1).unwrap().days(), 11017);
+        assert!("2022-02-29".parse::<Date>().is_err());
+        assert!("2024-02-29".parse::<Date>().is_ok());
+        assert!("15/01/2022".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn test_puzzle_numbers() {
+        assert_eq!(Schedule::number(Schedule::FIRST_DAY), Some(0));
+        // puzzle 210 was played on 2022-01-15
+        let date = "2022-01-15".parse().unwrap();
+        assert_eq!(Schedule::number(date), Some(210));
+        assert_eq!(Schedule::date(210), Some(date));
+        assert_eq!(Schedule::date(usize::MAX), None);
+        assert_eq!(Schedule::number("2021-06-18".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn test_answers() {
+        let schedule = Schedule::new(["cigar", "REBUT", "sissy"]).unwrap();
+        assert_eq!(schedule.len(), 3);
+        assert_eq!(schedule.answer(1), Some("rebut"));
+        assert_eq!(schedule.answer(3), None);
+        assert_eq!(
+            schedule.answer_on(Schedule::date(2).unwrap()),
+            Some("sissy")
+        );
+        assert_eq!(schedule.answers(1..10), ["rebut", "sissy"]);
+        assert!(schedule.answers(5..10).is_empty());
+        assert!(Schedule::new(["cigar", "xyzzy"]).is_err());
+    }
+}
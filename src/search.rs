@@ -0,0 +1,248 @@
+//! A small query language to look up the words of the dictionary that fit
+//! what is known about the answer.
+//!
+//! A query is a list of whitespace separated terms, letters are case insensitive:
+//!
+//! - `_r_ne`: the letter at every position, `_`, `?` or `.` when unknown
+//! - `+a`, `+ee`, `+e2`: letters the word needs, repeat the letter or add a
+//!   count to need it more than once
+//! - `-tso`: letters the word does not have
+//! - `!a1`, `!a13`: letters that are not at the given positions (1 to 5), add
+//!   `+a` as well for a letter that is in the word but misplaced
+//!
+//! `_r_ne +a -t` asks for the words that fit `_R_NE`, have an A somewhere and no T.
+
+use crate::algorithms::Constraints;
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    /// A positional pattern that is not 5 letters or wildcards long
+    InvalidPattern(String),
+    /// A character that is not a letter where a letter was expected
+    InvalidLetter(char),
+    /// A position that is not between 1 and 5
+    InvalidPosition(char),
+    /// A term the query language does not know about
+    UnknownTerm(String),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::InvalidPattern(pattern) => write!(
+                f,
+                "invalid pattern '{}', expected 5 letters or wildcards (_, ?, .)",
+                pattern
+            ),
+            QueryError::InvalidLetter(c) => write!(f, "'{}' is not a letter", c),
+            QueryError::InvalidPosition(c) => {
+                write!(f, "invalid position '{}', expected 1 to 5", c)
+            }
+            QueryError::UnknownTerm(term) => write!(f, "unknown term '{}'", term),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// A parsed search query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Query {
+    pub constraints: Constraints,
+}
+
+impl Query {
+    /// Checks if the word fits the query
+    pub fn matches(&self, word: &str) -> bool {
+        self.constraints.matches(word)
+    }
+
+    /// Returns the words that fit the query, the most frequent first
+    pub fn search(&self, frequencies: &BTreeMap<&'static str, u32>) -> Vec<(&'static str, u32)> {
+        let mut words: Vec<_> = frequencies
+            .iter()
+            .filter(|(word, _)| self.matches(word))
+            .map(|(&word, &count)| (word, count))
+            .collect();
+
+        words.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        words
+    }
+
+    fn letter(c: char) -> Result<char, QueryError> {
+        if c.is_ascii_alphabetic() {
+            Ok(c.to_ascii_lowercase())
+        } else {
+            Err(QueryError::InvalidLetter(c))
+        }
+    }
+
+    fn parse_pattern(&mut self, term: &str) -> Result<(), QueryError> {
+        if term.chars().count() != 5 {
+            return Err(QueryError::InvalidPattern(term.to_string()));
+        }
+
+        for (position, c) in term.chars().enumerate() {
+            match c {
+                '_' | '?' | '.' => {}
+                c if c.is_ascii_alphabetic() => self.constraints.fix(position, Query::letter(c)?),
+                _ => return Err(QueryError::InvalidPattern(term.to_string())),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_required(&mut self, letters: &str) -> Result<(), QueryError> {
+        let mut counts: Vec<(char, u8)> = vec![];
+
+        for c in letters.chars() {
+            match c.to_digit(10) {
+                // a count applies to the letter right before it
+                Some(count) => match counts.last_mut() {
+                    Some((_, last)) => *last = count as u8,
+                    None => return Err(QueryError::InvalidLetter(c)),
+                },
+                None => {
+                    let c = Query::letter(c)?;
+                    match counts.iter_mut().find(|(letter, _)| *letter == c) {
+                        Some((_, count)) => *count += 1,
+                        None => counts.push((c, 1)),
+                    }
+                }
+            }
+        }
+
+        for (c, count) in counts {
+            self.constraints.require(c, count);
+        }
+        Ok(())
+    }
+
+    fn parse_banned(&mut self, letters: &str) -> Result<(), QueryError> {
+        for c in letters.chars() {
+            self.constraints.ban(Query::letter(c)?);
+        }
+        Ok(())
+    }
+
+    fn parse_excluded(&mut self, term: &str) -> Result<(), QueryError> {
+        let mut chars = term.chars();
+        let c = match chars.next() {
+            Some(c) => Query::letter(c)?,
+            None => return Err(QueryError::UnknownTerm(format!("!{}", term))),
+        };
+
+        let mut any = false;
+        for position in chars {
+            match position.to_digit(10) {
+                Some(p @ 1..=5) => self.constraints.exclude(p as usize - 1, c),
+                _ => return Err(QueryError::InvalidPosition(position)),
+            }
+            any = true;
+        }
+        if !any {
+            return Err(QueryError::UnknownTerm(format!("!{}", term)));
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut query = Query::default();
+
+        for term in s.split_whitespace() {
+            if let Some(letters) = term.strip_prefix('+') {
+                query.parse_required(letters)?;
+            } else if let Some(letters) = term.strip_prefix('-') {
+                query.parse_banned(letters)?;
+            } else if let Some(exclusion) = term.strip_prefix('!') {
+                query.parse_excluded(exclusion)?;
+            } else if term.chars().count() == 5 {
+                query.parse_pattern(term)?;
+            } else {
+                return Err(QueryError::UnknownTerm(term.to_string()));
+            }
+        }
+
+        Ok(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::Utils;
+
+    fn search(query: &str) -> Vec<&'static str> {
+        let query: Query = query.parse().unwrap();
+        query
+            .search(&Utils::word_frequencies())
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect()
+    }
+
+    #[test]
+    fn test_pattern_with_required_and_banned_letters() {
+        let words = search("_R_NE +a -t");
+        assert!(!words.is_empty());
+        assert!(words.contains(&"crane"));
+        assert!(!words.contains(&"trane"));
+        assert!(words
+            .iter()
+            .all(|w| w.contains('a') && !w.contains('t') && &w[1..2] == "r" && &w[3..] == "ne"));
+    }
+
+    #[test]
+    fn test_results_are_sorted_by_frequency() {
+        let frequencies = Utils::word_frequencies();
+        let words = search("_R_NE +a -t");
+        assert!(words
+            .windows(2)
+            .all(|w| frequencies[w[0]] >= frequencies[w[1]]));
+    }
+
+    #[test]
+    fn test_counts_and_exclusions() {
+        assert_eq!("+ee".parse::<Query>(), "+e2".parse::<Query>());
+        assert!(search("+e3").iter().all(|w| w.matches('e').count() >= 3));
+
+        // an excluded position says nothing about the rest of the word
+        let words = search("!a1 !s5 +r");
+        assert!(!words.contains(&"roars"));
+        assert!(words.contains(&"bread"));
+        assert!(words
+            .iter()
+            .all(|w| w.contains('r') && !w.starts_with('a') && !w.ends_with('s')));
+
+        // a misplaced letter is both required and excluded
+        let words = search("!a1 !s5 +as");
+        assert!(!words.contains(&"bread"));
+        assert!(words.contains(&"scare"));
+        assert!(words.iter().all(|w| w.contains('a')
+            && w.contains('s')
+            && !w.starts_with('a')
+            && !w.ends_with('s')));
+    }
+
+    #[test]
+    fn test_invalid_queries() {
+        assert_eq!(
+            "_r_n".parse::<Query>(),
+            Err(QueryError::UnknownTerm("_r_n".to_string()))
+        );
+        assert_eq!(
+            "_r_n3".parse::<Query>(),
+            Err(QueryError::InvalidPattern("_r_n3".to_string()))
+        );
+        assert_eq!("-t4".parse::<Query>(), Err(QueryError::InvalidLetter('4')));
+        assert_eq!(
+            "!a6".parse::<Query>(),
+            Err(QueryError::InvalidPosition('6'))
+        );
+    }
+}
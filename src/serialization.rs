@@ -0,0 +1,121 @@
+//! Serde support for the core types.
+//!
+//! A `Score` is written as its letter, `C`, `M` or `I`, and an attempt the
+//! way it displays, `"tares:IMMII"`. An attempt read back owns its word.
+//! `WordEntropy` and `WordScore` hold `'static` words, which are looked up in
+//! the lexicon when read back and fail for words the solvers do not know.
+
+use crate::{
+    algorithms::{Utils, WordEntropy, WordScore},
+    Attempt, Score,
+};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::BTreeMap, sync::OnceLock};
+
+impl Serialize for Score {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_char(self.to_string().chars().next().expect("one letter"))
+    }
+}
+
+impl<'de> Deserialize<'de> for Score {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match char::deserialize(deserializer)? {
+            'C' => Ok(Score::Correct),
+            'M' => Ok(Score::Misplaced),
+            'I' => Ok(Score::Incorrect),
+            c => Err(D::Error::custom(format!(
+                "'{}' is not one of C, M and I",
+                c
+            ))),
+        }
+    }
+}
+
+impl Serialize for Attempt<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Attempt<'_> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let attempt: Attempt<'static> = String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)?;
+        Ok(attempt)
+    }
+}
+
+/// The `'static` word of the lexicon
+fn lexicon_word<E: Error>(word: &str) -> Result<&'static str, E> {
+    static LEXICON: OnceLock<BTreeMap<&'static str, u32>> = OnceLock::new();
+    LEXICON
+        .get_or_init(Utils::word_frequencies)
+        .get_key_value(word)
+        .map(|(&word, _)| word)
+        .ok_or_else(|| E::custom(format!("'{}' is not in the lexicon", word)))
+}
+
+impl<'de> Deserialize<'de> for WordEntropy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Raw {
+            word: String,
+            entropy: f64,
+        }
+        let raw = Raw::deserialize(deserializer)?;
+        Ok(WordEntropy::new(lexicon_word(&raw.word)?, raw.entropy))
+    }
+}
+
+impl<'de> Deserialize<'de> for WordScore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Raw {
+            word: String,
+            score: f64,
+        }
+        let raw = Raw::deserialize(deserializer)?;
+        Ok(WordScore::new(lexicon_word(&raw.word)?, raw.score))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        algorithms::{WordEntropy, WordScore},
+        Attempt, Score,
+    };
+
+    #[test]
+    fn test_attempt_is_written_as_it_displays() {
+        let attempt: Attempt = "tares:IMMII".parse().unwrap();
+        let json = serde_json::to_string(&attempt).unwrap();
+        assert_eq!(json, r#""tares:IMMII""#);
+
+        // the attempt outlives the text it was read from
+        let read: Attempt<'static> = serde_json::from_str(&json.clone()).unwrap();
+        assert_eq!(read, attempt);
+        let history: Vec<Attempt<'static>> =
+            serde_json::from_reader(r#"["tares:IMMII","cigar:CCCCC"]"#.as_bytes()).unwrap();
+        assert_eq!(history[1].word, "cigar");
+
+        assert_eq!(serde_json::to_string(&Score::Misplaced).unwrap(), r#""M""#);
+        assert!(serde_json::from_str::<Score>(r#""X""#).is_err());
+        assert!(serde_json::from_str::<Attempt>(r#""tares:IMM""#).is_err());
+        assert!(serde_json::from_str::<Attempt>(r#"{"word":"tares","score":"IMMII"}"#).is_err());
+    }
+
+    #[test]
+    fn test_words_are_looked_up_in_the_lexicon() {
+        let entropy = WordEntropy::new("tares", 6.2);
+        let json = serde_json::to_string(&entropy).unwrap();
+        let read: WordEntropy = serde_json::from_str(&json).unwrap();
+        assert_eq!((read.word, read.entropy), ("tares", 6.2));
+
+        let score: WordScore = serde_json::from_str(r#"{"word":"cigar","score":1.5}"#).unwrap();
+        assert_eq!(score.word, "cigar");
+        assert!(serde_json::from_str::<WordScore>(r#"{"word":"xyzzy","score":1.5}"#).is_err());
+    }
+}
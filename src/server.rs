@@ -0,0 +1,573 @@
+//! A JSON API over HTTP to play games and ask the solvers for help, only
+//! served on localhost.
+//!
+//! Histories are lists of attempts, `{"word": "tares", "pattern": "IMMII"}`.
+//!
+//! - `POST /games` starts a game, with an optional `answer` or `seed` and
+//!   `max_guesses` and `hard`. Answers with the `id` of the game.
+//! - `GET /games/{id}` is the state of the game, `DELETE /games/{id}` drops it.
+//! - `POST /games/{id}/guesses` with a `guess` plays it and answers with its
+//!   `pattern` and the state of the game.
+//! - `POST /suggestions` with a `history`, an optional `solver` spec (see
+//!   `SolverRegistry::builtins`, the external solver is not served) and `n`
+//!   ranks the best next guesses with their scores.
+//! - `POST /candidates` with a `history` and an optional `limit` lists the
+//!   words that can still be the answer, most common first.
+//!
+//! Bodies have to be sent as `application/json`, which a web page cannot do
+//! on another origin without asking first. Errors come back with a 4xx status
+//! and `{"error": "..."}`.
+
+use crate::{
+    algorithms::{Constraints, SolverRegistry, SolverSpec, Utils},
+    referee::{Game, Referee, Rules},
+    search::Query,
+    Attempt, Score, Solver,
+};
+use serde_json::{json, Value};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt, io,
+    net::SocketAddr,
+    panic::{self, AssertUnwindSafe},
+};
+use tiny_http::{Header, Method, Response};
+
+/// Why a request failed, with the HTTP status to answer with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: 400,
+            message: message.into(),
+        }
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            status: 404,
+            message: message.into(),
+        }
+    }
+
+    fn unsupported_media_type(message: impl Into<String>) -> Self {
+        Self {
+            status: 415,
+            message: message.into(),
+        }
+    }
+
+    fn internal(message: impl Into<String>) -> Self {
+        Self {
+            status: 500,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+fn game_to_json(game: &Game, id: u64) -> Value {
+    let status = match (game.won(), game.is_over()) {
+        (true, _) => "won",
+        (false, true) => "lost",
+        (false, false) => "playing",
+    };
+    let mut state = json!({
+        "id": id,
+        "status": status,
+        "max_guesses": game.rules.max_guesses,
+        "hard": game.rules.hard_mode,
+        "history": history_to_json(&game.attempts),
+    });
+    // the answer is only given away once the game is over
+    if game.is_over() {
+        state["answer"] = json!(game.answer);
+    }
+    state
+}
+
+/// The games in play and everything needed to answer requests
+pub struct Api {
+    sessions: HashMap<u64, Game>,
+    next_id: u64,
+    referee: Referee,
+    registry: SolverRegistry,
+    /// The solvers asked for so far by their spec, they keep what they worked
+    /// out, e.g. the ranking of the first move
+    solvers: HashMap<String, Box<dyn Solver>>,
+    lexicon: BTreeMap<&'static str, u32>,
+}
+
+impl Default for Api {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Api {
+    /// The most solvers kept, all of them are dropped to make room for another
+    const MAX_SOLVERS: usize = 8;
+
+    pub fn new() -> Self {
+        Self {
+            sessions: HashMap::new(),
+            next_id: 1,
+            referee: Referee::default(),
+            registry: SolverRegistry::builtins(),
+            solvers: HashMap::new(),
+            lexicon: Utils::word_frequencies(),
+        }
+    }
+
+    /// Answers the request to `path` with its JSON `body`, if it had one
+    pub fn handle(&mut self, method: &Method, path: &str, body: Value) -> Result<Value, ApiError> {
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match (method, segments.as_slice()) {
+            (Method::Post, ["games"]) => self.start(&body),
+            (Method::Get, ["games", id]) => {
+                let id = parse_id(id)?;
+                Ok(game_to_json(self.session(id)?, id))
+            }
+            (Method::Delete, ["games", id]) => {
+                let id = parse_id(id)?;
+                match self.sessions.remove(&id) {
+                    Some(_) => Ok(json!({ "id": id })),
+                    None => Err(ApiError::not_found(format!("no game {}", id))),
+                }
+            }
+            (Method::Post, ["games", id, "guesses"]) => self.guess(parse_id(id)?, &body),
+            (Method::Post, ["suggestions"]) => self.suggestions(&body),
+            (Method::Post, ["candidates"]) => self.candidates(&body),
+            _ => Err(ApiError::not_found(format!(
+                "no endpoint {} {}",
+                method, path
+            ))),
+        }
+    }
+
+    fn session(&self, id: u64) -> Result<&Game, ApiError> {
+        self.sessions
+            .get(&id)
+            .ok_or_else(|| ApiError::not_found(format!("no game {}", id)))
+    }
+
+    fn start(&mut self, body: &Value) -> Result<Value, ApiError> {
+        let defaults = Rules::default();
+        let rules = Rules {
+            max_guesses: match optional_u64(body, "max_guesses")? {
+                None => defaults.max_guesses,
+                // a game without a guess would be over before it starts
+                Some(0) => return Err(ApiError::bad_request("max_guesses should be at least 1")),
+                Some(max) => usize::try_from(max)
+                    .map_err(|_| ApiError::bad_request("max_guesses is too large"))?,
+            },
+            hard_mode: match body.get("hard") {
+                None | Some(Value::Null) => false,
+                Some(hard) => hard
+                    .as_bool()
+                    .ok_or_else(|| ApiError::bad_request("hard should be true or false"))?,
+            },
+            ..defaults
+        };
+
+        let answer = match optional_str(body, "answer")? {
+            Some(answer) => {
+                let answer = answer.to_ascii_lowercase();
+                match self.lexicon.get_key_value(answer.as_str()) {
+                    Some((&answer, _)) => answer,
+                    None => {
+                        return Err(ApiError::bad_request(format!("'{}' is not a word", answer)))
+                    }
+                }
+            }
+            None => {
+                let seed = optional_u64(body, "seed")?.unwrap_or_else(rand::random);
+                self.referee.random_answer(seed)
+            }
+        };
+
+        let id = self.next_id;
+        self.next_id += 1;
+        let game = Game::new(answer, rules);
+        let state = game_to_json(&game, id);
+        self.sessions.insert(id, game);
+        Ok(state)
+    }
+
+    fn guess(&mut self, id: u64, body: &Value) -> Result<Value, ApiError> {
+        let guess = required_str(body, "guess")?;
+        let game = self
+            .sessions
+            .get_mut(&id)
+            .ok_or_else(|| ApiError::not_found(format!("no game {}", id)))?;
+        let score = game
+            .guess(&self.referee, guess)
+            .map_err(ApiError::bad_request)?;
+
+        let mut state = game_to_json(game, id);
+        state["pattern"] = json!(Score::to_pattern(&score));
+        Ok(state)
+    }
+
+    fn suggestions(&mut self, body: &Value) -> Result<Value, ApiError> {
+        let history = history_from_json(body)?;
+        let spec: SolverSpec = optional_str(body, "solver")?
+            .unwrap_or("entropy")
+            .parse()
+            .map_err(ApiError::bad_request)?;
+        let n = optional_u64(body, "n")?.unwrap_or(5) as usize;
+
+        let key = spec.to_string();
+        if !self.solvers.contains_key(&key) {
+            let solver = self.registry.build(&spec).map_err(ApiError::bad_request)?;
+            if self.solvers.len() >= Api::MAX_SOLVERS {
+                self.solvers.clear();
+            }
+            self.solvers.insert(key.clone(), solver);
+        }
+        let solver = self.solvers.get_mut(&key).expect("the solver was added");
+
+        let suggestions: Vec<Value> = solver
+            .rank(&history, n)
+            .into_iter()
+            .map(|evaluation| {
+                json!({
+                    "word": evaluation.word,
+                    "score": evaluation.score,
+                    "entropy": evaluation.entropy,
+                    "expected_remaining": evaluation.expected_remaining,
+                    "worst_case": evaluation.worst_case,
+                    "win_probability": evaluation.win_probability,
+                    "is_candidate": evaluation.is_candidate,
+                })
+            })
+            .collect();
+        Ok(json!({ "solver": solver.name(), "suggestions": suggestions }))
+    }
+
+    fn candidates(&self, body: &Value) -> Result<Value, ApiError> {
+        let history = history_from_json(body)?;
+        let limit = optional_u64(body, "limit")?.map_or(usize::MAX, |limit| limit as usize);
+
+        let query = Query {
+            constraints: Constraints::from_attempts(&history),
+        };
+        let words = query.search(&self.lexicon);
+        let candidates: Vec<&str> = words.iter().take(limit).map(|(word, _)| *word).collect();
+        Ok(json!({ "count": words.len(), "candidates": candidates }))
+    }
+}
+
+fn parse_id(id: &str) -> Result<u64, ApiError> {
+    id.parse()
+        .map_err(|_| ApiError::not_found(format!("no game {}", id)))
+}
+
+fn optional_str<'a>(body: &'a Value, key: &str) -> Result<Option<&'a str>, ApiError> {
+    match body.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_str()
+            .map(Some)
+            .ok_or_else(|| ApiError::bad_request(format!("{} should be a string", key))),
+    }
+}
+
+fn required_str<'a>(body: &'a Value, key: &str) -> Result<&'a str, ApiError> {
+    optional_str(body, key)?.ok_or_else(|| ApiError::bad_request(format!("{} is missing", key)))
+}
+
+fn optional_u64(body: &Value, key: &str) -> Result<Option<u64>, ApiError> {
+    match body.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| ApiError::bad_request(format!("{} should be a positive number", key))),
+    }
+}
+
+fn history_to_json(attempts: &[Attempt]) -> Value {
+    attempts
+        .iter()
+        .map(
+            |attempt| json!({ "word": attempt.word, "pattern": Score::to_pattern(&attempt.score) }),
+        )
+        .collect()
+}
+
+fn history_from_json(body: &Value) -> Result<Vec<Attempt<'static>>, ApiError> {
+    let attempts = match body.get("history") {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::Array(attempts)) => attempts,
+        Some(_) => {
+            return Err(ApiError::bad_request(
+                "history should be a list of attempts",
+            ))
+        }
+    };
+
+    attempts
+        .iter()
+        .map(|attempt| {
+            let word = Referee::normalize(required_str(attempt, "word")?)
+                .map_err(ApiError::bad_request)?;
+            let pattern = required_str(attempt, "pattern")?;
+            let score = Score::from_pattern(&pattern.to_ascii_uppercase()).ok_or_else(|| {
+                ApiError::bad_request(format!("'{}' is not a pattern of C, M and I", pattern))
+            })?;
+            Ok(Attempt {
+                word: Cow::Owned(word),
+                score,
+            })
+        })
+        .collect()
+}
+
+/// The HTTP server in front of an `Api`
+pub struct Server {
+    http: tiny_http::Server,
+    api: Api,
+}
+
+impl Server {
+    /// Listens on the port of localhost, 0 picks a free one
+    pub fn bind(port: u16) -> io::Result<Self> {
+        let http = tiny_http::Server::http(("127.0.0.1", port)).map_err(io::Error::other)?;
+        Ok(Self {
+            http,
+            api: Api::new(),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.http
+            .server_addr()
+            .to_ip()
+            .expect("the server listens on an IP address")
+    }
+
+    /// Answers requests one after the other, until the process ends
+    pub fn serve(mut self) {
+        for mut request in self.http.incoming_requests() {
+            let json = request.headers().iter().any(|header| {
+                header.field.equiv("Content-Type")
+                    && header.value.as_str().split(';').next().map(str::trim)
+                        == Some("application/json")
+            });
+            let mut body = String::new();
+            let result = match request.as_reader().read_to_string(&mut body) {
+                Err(err) => Err(ApiError::bad_request(format!(
+                    "cannot read the body: {}",
+                    err
+                ))),
+                // a form or plain text can be posted by any web page
+                Ok(_) if !json && (*request.method() == Method::Post || !body.is_empty()) => Err(
+                    ApiError::unsupported_media_type("the body should be application/json"),
+                ),
+                Ok(_) if body.trim().is_empty() => Ok(Value::Null),
+                Ok(_) => serde_json::from_str(&body)
+                    .map_err(|err| ApiError::bad_request(format!("invalid JSON: {}", err))),
+            };
+            // the query string is not part of any endpoint
+            let path = request.url().split('?').next().unwrap_or("").to_string();
+            let api = &mut self.api;
+            let result = result.and_then(|body| {
+                // a request that makes a solver panic does not take the server down
+                panic::catch_unwind(AssertUnwindSafe(|| {
+                    api.handle(request.method(), &path, body)
+                }))
+                .unwrap_or_else(|_| Err(ApiError::internal("the request failed")))
+            });
+
+            let (status, value) = match result {
+                Ok(value) => (200, value),
+                Err(err) => (err.status, json!({ "error": err.message })),
+            };
+            let header = Header::from_bytes("Content-Type", "application/json")
+                .expect("the header is valid");
+            let response = Response::from_string(value.to_string())
+                .with_status_code(status)
+                .with_header(header);
+            // a client that went away does not stop the server
+            let _ = request.respond(response);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        sync::mpsc,
+        thread,
+    };
+
+    fn post(api: &mut Api, path: &str, body: Value) -> Result<Value, ApiError> {
+        api.handle(&Method::Post, path, body)
+    }
+
+    #[test]
+    fn test_plays_a_game() {
+        let mut api = Api::new();
+        let game = post(&mut api, "/games", json!({ "answer": "cigar" })).unwrap();
+        assert_eq!(game["status"], "playing");
+        assert!(game.get("answer").is_none());
+        let id = game["id"].as_u64().unwrap();
+        let path = format!("/games/{}/guesses", id);
+
+        let state = post(&mut api, &path, json!({ "guess": "tares" })).unwrap();
+        assert_eq!(state["pattern"], "IMMII");
+        assert_eq!(state["status"], "playing");
+
+        let err = post(&mut api, &path, json!({ "guess": "xyzzy" })).unwrap_err();
+        assert_eq!(err.status, 400);
+
+        let state = post(&mut api, &path, json!({ "guess": "CIGAR" })).unwrap();
+        assert_eq!(state["status"], "won");
+        assert_eq!(state["answer"], "cigar");
+        assert_eq!(state["history"].as_array().unwrap().len(), 2);
+        assert!(post(&mut api, &path, json!({ "guess": "cigar" })).is_err());
+
+        let games = format!("/games/{}", id);
+        assert_eq!(
+            api.handle(&Method::Get, &games, Value::Null).unwrap(),
+            state_without_pattern(state)
+        );
+        api.handle(&Method::Delete, &games, Value::Null).unwrap();
+        let err = api.handle(&Method::Get, &games, Value::Null).unwrap_err();
+        assert_eq!(err.status, 404);
+    }
+
+    fn state_without_pattern(mut state: Value) -> Value {
+        state.as_object_mut().unwrap().remove("pattern");
+        state
+    }
+
+    #[test]
+    fn test_hard_mode_and_guess_limit() {
+        let mut api = Api::new();
+        let body = json!({ "answer": "cigar", "hard": true, "max_guesses": 2 });
+        let id = post(&mut api, "/games", body).unwrap()["id"]
+            .as_u64()
+            .unwrap();
+        let path = format!("/games/{}/guesses", id);
+
+        post(&mut api, &path, json!({ "guess": "tares" })).unwrap();
+        // the R and the A were found and have to be used
+        assert!(post(&mut api, &path, json!({ "guess": "plumb" })).is_err());
+        let state = post(&mut api, &path, json!({ "guess": "roast" })).unwrap();
+        assert_eq!(state["status"], "lost");
+        assert_eq!(state["answer"], "cigar");
+
+        let error = post(&mut api, "/games", json!({ "max_guesses": 0 })).unwrap_err();
+        assert_eq!(error.status, 400);
+    }
+
+    #[test]
+    fn test_suggestions_and_candidates() {
+        let mut api = Api::new();
+        let history = json!([
+            { "word": "tares", "pattern": "IMMII" },
+            { "word": "grail", "pattern": "MMMMI" },
+        ]);
+
+        let body = json!({ "history": history, "solver": "entropy", "n": 2 });
+        let suggestions = post(&mut api, "/suggestions", body).unwrap();
+        assert_eq!(suggestions["solver"], "entropy");
+        let suggestions = suggestions["suggestions"].as_array().unwrap();
+        assert!(!suggestions.is_empty() && suggestions.len() <= 2);
+        assert!(suggestions[0]["entropy"].is_number());
+
+        let body = json!({ "history": history, "limit": 3 });
+        let candidates = post(&mut api, "/candidates", body).unwrap();
+        let words = candidates["candidates"].as_array().unwrap();
+        assert!(words.contains(&json!("cigar")));
+        assert!(candidates["count"].as_u64().unwrap() >= words.len() as u64);
+
+        let bad = json!({ "history": [{ "word": "tares", "pattern": "XXXXX" }] });
+        assert_eq!(post(&mut api, "/candidates", bad).unwrap_err().status, 400);
+        let bad = json!({ "solver": "genius" });
+        assert_eq!(post(&mut api, "/suggestions", bad).unwrap_err().status, 400);
+        // no program is run on behalf of a client
+        let bad = json!({ "solver": "external:command=touch /tmp/wordl-pwned" });
+        assert_eq!(post(&mut api, "/suggestions", bad).unwrap_err().status, 400);
+        assert_eq!(
+            post(&mut api, "/nothing", Value::Null).unwrap_err().status,
+            404
+        );
+    }
+
+    fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> String {
+        request_as(addr, method, path, "application/json", body)
+    }
+
+    fn request_as(
+        addr: SocketAddr,
+        method: &str,
+        path: &str,
+        content_type: &str,
+        body: &str,
+    ) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+             Content-Type: {}\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            content_type,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_serves_over_http() {
+        // the solvers of the server stay on its thread
+        let (sender, addr) = mpsc::channel();
+        thread::spawn(move || {
+            let server = Server::bind(0).unwrap();
+            sender.send(server.addr()).unwrap();
+            server.serve()
+        });
+        let addr = addr.recv().unwrap();
+        assert!(addr.ip().is_loopback());
+
+        let response = request(addr, "POST", "/games", r#"{"answer": "cigar"}"#);
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains(r#""status":"playing""#));
+
+        let response = request(addr, "POST", "/games/1/guesses", r#"{"guess": "cigar"}"#);
+        assert!(response.contains(r#""pattern":"CCCCC""#));
+
+        let response = request(addr, "POST", "/games", "{not json");
+        assert!(response.starts_with("HTTP/1.1 400"));
+        assert!(response.contains("invalid JSON"));
+
+        // what a form on another site would send
+        let response = request_as(addr, "POST", "/games", "text/plain", r#"{"seed": 1}"#);
+        assert!(response.starts_with("HTTP/1.1 415"));
+        let response = request_as(addr, "GET", "/games/1", "text/plain", "");
+        assert!(response.starts_with("HTTP/1.1 200"));
+    }
+}
@@ -0,0 +1,345 @@
+//! Statistics of the games played by a person, kept like the real game does:
+//! games played, wins, streaks and how many guesses the wins took.
+//!
+//! Every mode has its own record, so hard mode wins do not mix with normal
+//! ones and a game with more guesses does not count as a classic one. The
+//! records are saved as JSON with the `stats` feature, one object per mode:
+//!
+//! ```json
+//! {
+//!   "classic-5x6-hard": {
+//!     "played": 12,
+//!     "won": 10,
+//!     "current_streak": 3,
+//!     "max_streak": 5,
+//!     "distribution": [0, 1, 4, 3, 2, 0]
+//!   }
+//! }
+//! ```
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+#[cfg(feature = "stats")]
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The kind of game a record is kept for
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct Mode {
+    pub variant: String,
+    pub length: usize,
+    pub max_guesses: usize,
+    pub hard: bool,
+}
+
+impl Mode {
+    pub fn classic(hard: bool, max_guesses: usize) -> Self {
+        Self {
+            variant: "classic".to_string(),
+            length: 5,
+            max_guesses,
+            hard,
+        }
+    }
+
+    /// Fibble with one lie per row is `fibble`, with two `fibble2` and so on
+    pub fn fibble(lies: usize, max_guesses: usize) -> Self {
+        let variant = match lies {
+            1 => "fibble".to_string(),
+            lies => format!("fibble{}", lies),
+        };
+        Self {
+            variant,
+            length: 5,
+            max_guesses,
+            hard: false,
+        }
+    }
+}
+
+/// Writes the mode as `variant-lengthxguesses-difficulty`, e.g.
+/// `classic-5x6-normal` for five letters and six guesses
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let difficulty = if self.hard { "hard" } else { "normal" };
+        write!(
+            f,
+            "{}-{}x{}-{}",
+            self.variant, self.length, self.max_guesses, difficulty
+        )
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{}' should look like classic-5x6-normal", s);
+        let mut parts = s.trim().rsplitn(3, '-');
+        let hard = match parts.next() {
+            Some("hard") => true,
+            Some("normal") => false,
+            _ => return Err(invalid()),
+        };
+        let (length, max_guesses) = parts
+            .next()
+            .and_then(|size| size.split_once('x'))
+            .and_then(|(length, guesses)| Some((length.parse().ok()?, guesses.parse().ok()?)))
+            .ok_or_else(invalid)?;
+        let variant = parts
+            .next()
+            .filter(|variant| !variant.is_empty())
+            .ok_or_else(invalid)?;
+
+        Ok(Self {
+            variant: variant.to_ascii_lowercase(),
+            length,
+            max_guesses,
+            hard,
+        })
+    }
+}
+
+impl From<Mode> for String {
+    fn from(mode: Mode) -> Self {
+        mode.to_string()
+    }
+}
+
+impl TryFrom<String> for Mode {
+    type Error = String;
+
+    fn try_from(mode: String) -> Result<Self, Self::Error> {
+        mode.parse()
+    }
+}
+
+/// How a game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Found the answer with that many guesses
+    Won(usize),
+    Lost,
+}
+
+/// The statistics of one mode
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Record {
+    pub played: u32,
+    pub won: u32,
+    pub current_streak: u32,
+    pub max_streak: u32,
+    /// Number of wins by number of guesses, the first is the wins in one
+    pub distribution: Vec<u32>,
+}
+
+impl Record {
+    /// Counts the game, a win has to have taken at least one guess
+    pub fn add(&mut self, outcome: Outcome) -> Result<(), String> {
+        if outcome == Outcome::Won(0) {
+            return Err("a game cannot be won without a guess".to_string());
+        }
+        self.played += 1;
+        match outcome {
+            Outcome::Won(guesses) => {
+                self.won += 1;
+                self.current_streak += 1;
+                self.max_streak = self.max_streak.max(self.current_streak);
+                if self.distribution.len() < guesses {
+                    self.distribution.resize(guesses, 0);
+                }
+                self.distribution[guesses - 1] += 1;
+            }
+            Outcome::Lost => self.current_streak = 0,
+        }
+        Ok(())
+    }
+
+    /// Share of the games that were won, from 0 to 100
+    pub fn win_percentage(&self) -> f64 {
+        if self.played == 0 {
+            return 0.0;
+        }
+        self.won as f64 * 100.0 / self.played as f64
+    }
+}
+
+/// Shows the record the way the game does after a game, with a bar per
+/// number of guesses
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Played {}  Win % {:.0}  Current streak {}  Max streak {}",
+            self.played,
+            self.win_percentage(),
+            self.current_streak,
+            self.max_streak
+        )?;
+        writeln!(f, "Guess distribution")?;
+        let most = self.distribution.iter().copied().max().unwrap_or(0).max(1);
+        // the six rows of the real game at least
+        for i in 0..self.distribution.len().max(6) {
+            let wins = self.distribution.get(i).copied().unwrap_or(0);
+            let bar = "#".repeat((wins * 30).div_ceil(most) as usize);
+            writeln!(f, "{:>2} | {} {}", i + 1, bar, wins)?;
+        }
+        Ok(())
+    }
+}
+
+/// The records of every mode
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Stats {
+    pub records: BTreeMap<Mode, Record>,
+}
+
+impl Stats {
+    /// `stats.json` in the data directory of the user, e.g.
+    /// `~/.local/share/wordl` on Linux
+    #[cfg(feature = "stats")]
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("wordl").join("stats.json"))
+    }
+
+    /// Reads the records, a missing file has none
+    #[cfg(feature = "stats")]
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the records, creating the directory if needed
+    #[cfg(feature = "stats")]
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_json())
+    }
+
+    /// The records as the JSON `save` writes
+    #[cfg(feature = "stats")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("the records are valid JSON")
+    }
+
+    /// Adds the game to the record of the mode and returns the record
+    pub fn add(&mut self, mode: Mode, outcome: Outcome) -> Result<&Record, String> {
+        let record = self.records.entry(mode).or_default();
+        record.add(outcome)?;
+        Ok(record)
+    }
+
+    /// One line per mode with the counts and the distribution, for
+    /// spreadsheets
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            String::from("mode,played,won,win_percentage,current_streak,max_streak,distribution\n");
+        for (mode, record) in &self.records {
+            let distribution: Vec<String> =
+                record.distribution.iter().map(|n| n.to_string()).collect();
+            csv.push_str(&format!(
+                "{},{},{},{:.1},{},{},{}\n",
+                mode,
+                record.played,
+                record.won,
+                record.win_percentage(),
+                record.current_streak,
+                record.max_streak,
+                distribution.join(" ")
+            ));
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaks_and_distribution() {
+        let mut record = Record::default();
+        for outcome in [
+            Outcome::Won(3),
+            Outcome::Won(4),
+            Outcome::Lost,
+            Outcome::Won(3),
+        ] {
+            record.add(outcome).unwrap();
+        }
+        assert_eq!(record.played, 4);
+        assert_eq!(record.won, 3);
+        assert_eq!(record.current_streak, 1);
+        assert_eq!(record.max_streak, 2);
+        assert_eq!(record.distribution, vec![0, 0, 2, 1]);
+        assert_eq!(record.win_percentage(), 75.0);
+
+        assert!(record.add(Outcome::Won(0)).is_err());
+        assert_eq!(record.played, 4);
+    }
+
+    #[test]
+    fn test_modes_are_kept_apart() {
+        let mut stats = Stats::default();
+        stats.add(Mode::classic(false, 6), Outcome::Won(4)).unwrap();
+        stats.add(Mode::classic(true, 6), Outcome::Lost).unwrap();
+        stats.add(Mode::classic(false, 8), Outcome::Lost).unwrap();
+        assert_eq!(stats.records.len(), 3);
+        assert!(stats.to_csv().contains("classic-5x6-hard,1,0,0.0,0,0,\n"));
+
+        let mode: Mode = "fibble-5x9-hard".parse().unwrap();
+        assert_eq!((mode.variant.as_str(), mode.max_guesses), ("fibble", 9));
+        assert_eq!(Mode::fibble(2, 9).to_string(), "fibble2-5x9-normal");
+        assert!("classic-5-hard".parse::<Mode>().is_err());
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn test_json_round_trip() {
+        let mut stats = Stats::default();
+        stats.add(Mode::classic(false, 6), Outcome::Won(4)).unwrap();
+        stats.add(Mode::classic(true, 6), Outcome::Lost).unwrap();
+
+        let json = stats.to_json();
+        assert!(json.contains("\"classic-5x6-normal\": {\n    \"played\": 1,\n    \"won\": 1"));
+        assert_eq!(serde_json::from_str::<Stats>(&json).unwrap(), stats);
+
+        assert!(
+            serde_json::from_str::<Stats>(r#"{"classic-5x6-normal": {"played": "x"}}"#).is_err()
+        );
+        assert!(serde_json::from_str::<Stats>(r#"{"classic": {}}"#).is_err());
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn test_load_and_save() {
+        let path = std::env::temp_dir()
+            .join(format!("wordl-stats-{}", std::process::id()))
+            .join("stats.json");
+        assert_eq!(Stats::load(&path).unwrap(), Stats::default());
+
+        let mut stats = Stats::default();
+        stats.add(Mode::classic(false, 6), Outcome::Won(2)).unwrap();
+        stats.save(&path).unwrap();
+        assert_eq!(Stats::load(&path).unwrap(), stats);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,523 @@
+//! Full screen terminal UI with the grid, a keyboard showing what is known
+//! about every letter and the best guesses of the solver.
+//!
+//! In assist mode the row is filled with the suggestion of the solver, the
+//! colors the game gave it are set with the arrows and space, and enter moves
+//! on to the next row. In play mode the words are typed and colored against
+//! a hidden answer, tab shows the suggestions.
+
+use crate::{
+    algorithms::{Constraints, GuessEvaluation, Utils},
+    referee::Referee,
+    search::Query,
+    Attempt, Score, Solver,
+};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Row, Table},
+    Frame,
+};
+use std::{borrow::Cow, collections::BTreeMap, io};
+
+/// Number of suggestions in the side panel
+const SUGGESTIONS: usize = 10;
+
+const KEYBOARD: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+/// What the UI is used for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Suggest guesses for a game played somewhere else
+    Assist,
+    /// Play a game against the answer
+    Play { answer: &'static str },
+}
+
+/// The state of the UI, updated by the keys and drawn every frame
+pub struct App<S> {
+    mode: Mode,
+    solver: S,
+    referee: Referee,
+    lexicon: BTreeMap<&'static str, u32>,
+    attempts: Vec<Attempt<'static>>,
+    /// The row being filled in
+    word: String,
+    /// The colors of the row being filled in, only used in assist mode
+    pattern: [Score; 5],
+    cursor: usize,
+    suggestions: Vec<GuessEvaluation>,
+    /// Index of the suggestion in the row, tab moves to the next one
+    suggestion: usize,
+    candidates: usize,
+    show_suggestions: bool,
+    message: String,
+    over: bool,
+}
+
+impl<S: Solver> App<S> {
+    pub fn new(mode: Mode, solver: S, referee: Referee) -> Self {
+        let show_suggestions = mode == Mode::Assist;
+        let mut app = Self {
+            mode,
+            solver,
+            referee,
+            lexicon: Utils::word_frequencies(),
+            attempts: Vec::new(),
+            word: String::new(),
+            pattern: [Score::Incorrect; 5],
+            cursor: 0,
+            suggestions: Vec::new(),
+            suggestion: 0,
+            candidates: 0,
+            show_suggestions,
+            message: String::new(),
+            over: false,
+        };
+        app.next_row();
+        app
+    }
+
+    pub fn attempts(&self) -> &[Attempt<'static>] {
+        &self.attempts
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    /// Ranks the guesses after the attempts and clears the row
+    fn next_row(&mut self) {
+        self.suggestions = self.solver.rank(&self.attempts, SUGGESTIONS);
+        self.suggestion = 0;
+        self.candidates = Query {
+            constraints: Constraints::from_attempts(&self.attempts),
+        }
+        .search(&self.lexicon)
+        .len();
+        self.pattern = [Score::Incorrect; 5];
+        self.cursor = 0;
+        self.word = match (&self.mode, self.suggestions.first()) {
+            (Mode::Assist, Some(evaluation)) => evaluation.word.to_string(),
+            _ => String::new(),
+        };
+        if self.mode == Mode::Assist && self.suggestions.is_empty() {
+            self.message = "No word fits the colors, backspace to undo the last row".to_string();
+        }
+    }
+
+    /// Handles a key, returns false once the UI should close
+    pub fn handle(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Esc => return false,
+            KeyCode::Enter if self.over => return false,
+            _ if self.over => {}
+            KeyCode::Enter => self.submit(),
+            KeyCode::Tab => self.tab(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Char(c) if c.is_ascii_alphabetic() => self.type_letter(c),
+            KeyCode::Char(' ') | KeyCode::Up if self.mode == Mode::Assist => self.cycle(1),
+            KeyCode::Down if self.mode == Mode::Assist => self.cycle(2),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(4),
+            _ => {}
+        }
+        true
+    }
+
+    fn type_letter(&mut self, c: char) {
+        let c = c.to_ascii_lowercase();
+        match self.mode {
+            Mode::Play { .. } => {
+                if self.word.len() < 5 {
+                    self.word.push(c);
+                }
+            }
+            Mode::Assist => {
+                let mut letters: Vec<char> = format!("{:5}", self.word).chars().collect();
+                letters[self.cursor] = c;
+                self.word = letters
+                    .into_iter()
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string();
+                self.cursor = (self.cursor + 1).min(4);
+            }
+        }
+    }
+
+    fn backspace(&mut self) {
+        match self.mode {
+            Mode::Play { .. } => {
+                self.word.pop();
+            }
+            // an empty row goes back to the previous one to fix its colors
+            Mode::Assist if self.word.is_empty() => {
+                if let Some(attempt) = self.attempts.pop() {
+                    self.next_row();
+                    self.word = attempt.word.into_owned();
+                    self.pattern = attempt.score;
+                    self.message.clear();
+                }
+            }
+            Mode::Assist => {
+                self.word.pop();
+                self.cursor = self.word.len().min(4);
+            }
+        }
+    }
+
+    /// Moves the color of the tile under the cursor forward by `steps`
+    fn cycle(&mut self, steps: usize) {
+        const ORDER: [Score; 3] = [Score::Incorrect, Score::Misplaced, Score::Correct];
+        let tile = &mut self.pattern[self.cursor];
+        let index = ORDER.iter().position(|score| score == tile).unwrap_or(0);
+        *tile = ORDER[(index + steps) % ORDER.len()];
+    }
+
+    fn tab(&mut self) {
+        match self.mode {
+            Mode::Play { .. } => self.show_suggestions = !self.show_suggestions,
+            Mode::Assist if !self.suggestions.is_empty() => {
+                self.suggestion = (self.suggestion + 1) % self.suggestions.len();
+                self.word = self.suggestions[self.suggestion].word.to_string();
+            }
+            Mode::Assist => {}
+        }
+    }
+
+    fn submit(&mut self) {
+        if self.word.len() < 5 {
+            self.message = "Not enough letters".to_string();
+            return;
+        }
+        if let Some(reason) = self.referee.check(&self.attempts, &self.word) {
+            self.message = if reason.starts_with("not ") {
+                format!("'{}' is {}", self.word, reason)
+            } else {
+                format!("Hard mode, {}", reason)
+            };
+            return;
+        }
+
+        let score = match self.mode {
+            Mode::Assist => self.pattern,
+            Mode::Play { answer } => Score::color(answer, &self.word),
+        };
+        self.attempts.push(Attempt {
+            word: Cow::Owned(std::mem::take(&mut self.word)),
+            score,
+        });
+        self.message.clear();
+
+        let won = score == [Score::Correct; 5];
+        if won || self.attempts.len() >= self.referee.rules.max_guesses {
+            self.over = true;
+            self.message = match (won, &self.mode) {
+                (true, _) => format!("Solved in {} guesses", self.attempts.len()),
+                (false, Mode::Play { answer }) => format!("The answer was '{}'", answer),
+                (false, Mode::Assist) => "Out of guesses".to_string(),
+            };
+            self.message.push_str(", enter or esc to quit");
+            return;
+        }
+        self.next_row();
+    }
+
+    /// The best thing known about every letter, correct over misplaced over
+    /// incorrect
+    fn letters(&self) -> [Option<Score>; 26] {
+        let rank = |score: Score| match score {
+            Score::Correct => 2,
+            Score::Misplaced => 1,
+            Score::Incorrect => 0,
+        };
+        let mut letters = [None; 26];
+        for attempt in &self.attempts {
+            for (c, &score) in attempt.word.bytes().zip(&attempt.score) {
+                let known = &mut letters[(c - b'a') as usize];
+                if known.is_none_or(|known| rank(score) > rank(known)) {
+                    *known = Some(score);
+                }
+            }
+        }
+        letters
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let [board, panel] =
+            Layout::horizontal([Constraint::Min(40), Constraint::Length(44)]).areas(frame.area());
+        let rows = self.referee.rules.max_guesses as u16;
+        let [grid, keyboard, status] = Layout::vertical([
+            Constraint::Length(rows * 2 + 2),
+            Constraint::Length(5),
+            Constraint::Min(3),
+        ])
+        .areas(board);
+
+        let title = match self.mode {
+            Mode::Assist => " Wordle Assistant ",
+            Mode::Play { .. } => " Wordle ",
+        };
+        frame.render_widget(
+            Paragraph::new(self.grid()).block(Block::bordered().title(title)),
+            grid,
+        );
+        frame.render_widget(
+            Paragraph::new(self.keyboard()).block(Block::bordered()),
+            keyboard,
+        );
+
+        let help = match self.mode {
+            Mode::Assist => {
+                "type to change the word, tab next suggestion, ←/→ move, space or ↑/↓ color, \
+                 enter next row, backspace on an empty row undo, esc quit"
+            }
+            Mode::Play { .. } => "type a word, enter to guess, tab suggestions, esc quit",
+        };
+        let status_lines = vec![
+            Line::from(self.message.as_str()).style(Style::new().add_modifier(Modifier::BOLD)),
+            Line::from(help).style(Style::new().fg(Color::DarkGray)),
+        ];
+        frame.render_widget(
+            Paragraph::new(status_lines)
+                .wrap(ratatui::widgets::Wrap { trim: true })
+                .block(Block::bordered()),
+            status,
+        );
+
+        let block = Block::bordered().title(format!(" {} candidates ", self.candidates));
+        if self.show_suggestions {
+            frame.render_widget(self.table().block(block), panel);
+        } else {
+            frame.render_widget(Paragraph::new("tab to show").block(block), panel);
+        }
+    }
+
+    fn grid(&self) -> Vec<Line<'_>> {
+        let mut lines = Vec::new();
+        for row in 0..self.referee.rules.max_guesses {
+            let tiles: Vec<Span> = match self.attempts.get(row) {
+                Some(attempt) => attempt
+                    .word
+                    .chars()
+                    .zip(attempt.score)
+                    .map(|(c, score)| tile(Some(c), tile_style(Some(score))))
+                    .collect(),
+                None if row == self.attempts.len() && !self.over => (0..5)
+                    .map(|i| {
+                        let score = (self.mode == Mode::Assist).then_some(self.pattern[i]);
+                        let mut style = tile_style(score);
+                        if self.mode == Mode::Assist && i == self.cursor {
+                            style = style.add_modifier(Modifier::UNDERLINED | Modifier::BOLD);
+                        }
+                        tile(self.word.chars().nth(i), style)
+                    })
+                    .collect(),
+                None => (0..5).map(|_| tile(None, tile_style(None))).collect(),
+            };
+            lines.push(Line::from(tiles).centered());
+            lines.push(Line::default());
+        }
+        lines
+    }
+
+    fn keyboard(&self) -> Vec<Line<'_>> {
+        let letters = self.letters();
+        KEYBOARD
+            .iter()
+            .map(|row| {
+                let keys: Vec<Span> = row
+                    .bytes()
+                    .map(|c| {
+                        let style = match letters[(c - b'a') as usize] {
+                            Some(score) => tile_style(Some(score)),
+                            None => Style::new(),
+                        };
+                        Span::styled(format!(" {} ", c as char), style)
+                    })
+                    .collect();
+                Line::from(keys).centered()
+            })
+            .collect()
+    }
+
+    fn table(&self) -> Table<'_> {
+        let rows = self.suggestions.iter().enumerate().map(|(i, evaluation)| {
+            let style = match (
+                self.mode == Mode::Assist && i == self.suggestion,
+                evaluation.is_candidate,
+            ) {
+                (true, _) => Style::new().add_modifier(Modifier::REVERSED),
+                (false, true) => Style::new().fg(Color::Green),
+                (false, false) => Style::new(),
+            };
+            Row::new([
+                evaluation.word.to_string(),
+                format!("{:.3}", evaluation.score),
+                format!("{:.2}", evaluation.entropy),
+                format!("{:.1}", evaluation.expected_remaining),
+                evaluation.worst_case.to_string(),
+            ])
+            .style(style)
+        });
+        Table::new(
+            rows,
+            [
+                Constraint::Length(6),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(7),
+                Constraint::Length(6),
+            ],
+        )
+        .header(
+            Row::new(["word", "score", "entropy", "left", "worst"])
+                .style(Style::new().add_modifier(Modifier::BOLD)),
+        )
+    }
+}
+
+fn tile_style(score: Option<Score>) -> Style {
+    let background = match score {
+        Some(Score::Correct) => Color::Green,
+        Some(Score::Misplaced) => Color::Yellow,
+        Some(Score::Incorrect) => Color::DarkGray,
+        None => return Style::new().bg(Color::Black).fg(Color::White),
+    };
+    Style::new()
+        .bg(background)
+        .fg(Color::Black)
+        .add_modifier(Modifier::BOLD)
+}
+
+fn tile(c: Option<char>, style: Style) -> Span<'static> {
+    let c = c.map_or(' ', |c| c.to_ascii_uppercase());
+    Span::styled(format!(" {} ", c), style)
+}
+
+/// Runs the UI until it is closed, the terminal is restored even when
+/// drawing fails
+pub fn run<S: Solver>(app: &mut App<S>) -> io::Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = (|| loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.handle(key.code) {
+                return Ok(());
+            }
+        }
+    })();
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{algorithms::SolverRegistry, referee::Rules};
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn app(mode: Mode) -> App<Box<dyn Solver>> {
+        let solver = SolverRegistry::default().create("naive").unwrap();
+        App::new(mode, solver, Referee::default())
+    }
+
+    fn type_word(app: &mut App<Box<dyn Solver>>, word: &str) {
+        for c in word.chars() {
+            app.handle(KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_play() {
+        let mut app = app(Mode::Play { answer: "cigar" });
+        assert!(app.word.is_empty());
+
+        type_word(&mut app, "xyzzyq");
+        assert_eq!(app.word, "xyzzy");
+        app.handle(KeyCode::Enter);
+        assert!(app.attempts().is_empty());
+        assert!(app.message.contains("not in the dictionary"));
+
+        for _ in 0..5 {
+            app.handle(KeyCode::Backspace);
+        }
+        type_word(&mut app, "tares");
+        app.handle(KeyCode::Enter);
+        assert_eq!(app.attempts()[0].score, Score::color("cigar", "tares"));
+        let letters = app.letters();
+        assert_eq!(letters[(b'a' - b'a') as usize], Some(Score::Misplaced));
+        assert_eq!(letters[(b't' - b'a') as usize], Some(Score::Incorrect));
+        assert_eq!(letters[(b'c' - b'a') as usize], None);
+
+        type_word(&mut app, "cigar");
+        app.handle(KeyCode::Enter);
+        assert!(app.is_over());
+        assert!(!app.handle(KeyCode::Enter));
+    }
+
+    #[test]
+    fn test_assist_cycles_colors_and_undoes_rows() {
+        let mut app = app(Mode::Assist);
+        let suggestion = app.word.clone();
+        assert_eq!(suggestion.len(), 5);
+
+        app.handle(KeyCode::Right);
+        app.handle(KeyCode::Char(' '));
+        app.handle(KeyCode::Right);
+        app.handle(KeyCode::Down);
+        assert_eq!(
+            app.pattern,
+            [
+                Score::Incorrect,
+                Score::Misplaced,
+                Score::Correct,
+                Score::Incorrect,
+                Score::Incorrect
+            ]
+        );
+
+        app.handle(KeyCode::Enter);
+        assert_eq!(app.attempts().len(), 1);
+        assert_eq!(app.attempts()[0].word, suggestion);
+        assert_eq!(app.cursor, 0);
+        assert_ne!(app.word, suggestion);
+
+        // clearing the row and going back restores the previous one
+        for _ in 0..6 {
+            app.handle(KeyCode::Backspace);
+        }
+        assert!(app.attempts().is_empty());
+        assert_eq!(app.word, suggestion);
+        assert_eq!(app.pattern[2], Score::Correct);
+    }
+
+    #[test]
+    fn test_draws_the_board() {
+        let solver = SolverRegistry::default().create("naive").unwrap();
+        let referee = Referee::new(Rules {
+            max_guesses: 4,
+            ..Rules::default()
+        });
+        let mut app = App::new(Mode::Play { answer: "cigar" }, solver, referee);
+        type_word(&mut app, "tares");
+        app.handle(KeyCode::Enter);
+        app.handle(KeyCode::Tab);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains(" T  A  R  E  S "));
+        assert!(screen.contains("candidates"));
+        assert!(screen.contains(&app.suggestions[0].word.to_string()));
+    }
+}
@@ -0,0 +1,171 @@
+//! Bindings for JavaScript through `wasm-bindgen`, with the word lists
+//! embedded in the module.
+//!
+//! Histories are passed as a string of attempts separated by spaces, each
+//! written `word:PATTERN`, e.g. `"tares:IMMII grail:MMMMI"`.
+
+use crate::{
+    algorithms::{Constraints, SolverRegistry, Utils},
+    referee::{self, Referee, Rules},
+    search::Query,
+    Attempt, Score,
+};
+use wasm_bindgen::prelude::*;
+
+fn parse_history(history: &str) -> Result<Vec<Attempt<'static>>, JsError> {
+    history
+        .split_whitespace()
+        .map(|attempt| attempt.parse().map_err(|err: String| JsError::new(&err)))
+        .collect()
+}
+
+fn check_word(word: &str) -> Result<String, JsError> {
+    Referee::normalize(word).map_err(|err| JsError::new(&err))
+}
+
+/// The pattern the guess gets against the answer, e.g. `IMMII`
+#[wasm_bindgen]
+pub fn color(answer: &str, guess: &str) -> Result<String, JsError> {
+    let score = Score::color(&check_word(answer)?, &check_word(guess)?);
+    Ok(Score::to_pattern(&score))
+}
+
+/// The words that can still be the answer after the history, most common
+/// first
+#[wasm_bindgen]
+pub fn candidates(history: &str, limit: usize) -> Result<Vec<String>, JsError> {
+    let query = Query {
+        constraints: Constraints::from_attempts(&parse_history(history)?),
+    };
+    let words = query.search(&Utils::word_frequencies());
+    Ok(words
+        .into_iter()
+        .take(limit)
+        .map(|(word, _)| word.to_string())
+        .collect())
+}
+
+/// A guess ranked by a solver
+#[wasm_bindgen(getter_with_clone)]
+pub struct Suggestion {
+    pub word: String,
+    pub score: f64,
+    pub entropy: f64,
+    pub expected_remaining: f64,
+    pub worst_case: usize,
+    pub win_probability: f64,
+    pub is_candidate: bool,
+}
+
+/// The `n` best next guesses of the solver, `solver` is a spec of the
+/// `SolverRegistry` like `entropy:prior=sigmoid`
+#[wasm_bindgen]
+pub fn suggestions(history: &str, solver: &str, n: usize) -> Result<Vec<Suggestion>, JsError> {
+    let history = parse_history(history)?;
+    let mut solver = SolverRegistry::default()
+        .create(solver)
+        .map_err(|err| JsError::new(&err))?;
+
+    Ok(solver
+        .rank(&history, n)
+        .into_iter()
+        .map(|evaluation| Suggestion {
+            word: evaluation.word.into_owned(),
+            score: evaluation.score,
+            entropy: evaluation.entropy,
+            expected_remaining: evaluation.expected_remaining,
+            worst_case: evaluation.worst_case,
+            win_probability: evaluation.win_probability,
+            is_candidate: evaluation.is_candidate,
+        })
+        .collect())
+}
+
+/// A game against an answer picked with a seed
+#[wasm_bindgen]
+pub struct Game {
+    referee: Referee,
+    game: referee::Game,
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// The seed is a plain number rather than a `BigInt` on the JavaScript side
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u32, max_guesses: usize, hard: bool) -> Game {
+        let referee = Referee::default();
+        let rules = Rules {
+            max_guesses,
+            hard_mode: hard,
+            ..Rules::default()
+        };
+        let game = referee::Game::new(referee.random_answer(seed.into()), rules);
+        Game { referee, game }
+    }
+
+    /// Plays the guess and returns its pattern
+    pub fn guess(&mut self, word: &str) -> Result<String, JsError> {
+        match self.game.guess(&self.referee, word) {
+            Ok(score) => Ok(Score::to_pattern(&score)),
+            Err(err) => Err(JsError::new(&err)),
+        }
+    }
+
+    pub fn won(&self) -> bool {
+        self.game.won()
+    }
+
+    #[wasm_bindgen(js_name = isOver)]
+    pub fn is_over(&self) -> bool {
+        self.game.is_over()
+    }
+
+    /// The attempts so far, in the format the other functions take
+    pub fn history(&self) -> String {
+        let attempts: Vec<String> = self.game.attempts.iter().map(|a| a.to_string()).collect();
+        attempts.join(" ")
+    }
+
+    /// The answer, once the game is over
+    pub fn answer(&self) -> Option<String> {
+        self.is_over().then(|| self.game.answer.to_string())
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn test_color() {
+        assert_eq!(color("cigar", "tares").unwrap(), "IMMII");
+        assert!(color("cigar", "tar").is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_candidates_and_suggestions() {
+        let history = "tares:IMMII grail:MMMMI";
+        let words = candidates(history, 100).unwrap();
+        assert!(words.contains(&"cigar".to_string()));
+        assert!(candidates("tares", 10).is_err());
+
+        let ranked = suggestions(history, "entropy", 2).unwrap();
+        assert!(!ranked.is_empty() && ranked.len() <= 2);
+        assert!(suggestions("", "genius", 1).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_game() {
+        let mut game = Game::new(7, 6, false);
+        assert!(game.answer().is_none());
+        assert!(game.guess("xyzzy").is_err());
+        game.guess("tares").unwrap();
+
+        let answer = Referee::default().random_answer(7);
+        assert_eq!(game.guess(answer).unwrap(), "CCCCC");
+        assert!(game.won() && game.is_over());
+        assert_eq!(game.answer().as_deref(), Some(answer));
+        assert!(game.history().starts_with("tares:"));
+    }
+}